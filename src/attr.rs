@@ -0,0 +1,1209 @@
+use crate::case::Case;
+use crate::docs;
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use syn::meta::ParseNestedMeta;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    parenthesized, token, Attribute, Expr, ExprPath, ExprRange, Field, LitStr, Meta, Path,
+    RangeLimits, Result, Token, Type, WherePredicate,
+};
+
+/// Keys accepted by the struct-level `#[new(...)]` attribute.
+const STRUCT_KEYS: &[&str] = &[
+    "private",
+    "trace",
+    "free_fn",
+    "try_new",
+    "fallible",
+    "error",
+    "inline",
+    "no_must_use",
+    "hot",
+    "doc_defaults",
+    "doc_args",
+    "metadata",
+    "macro",
+    "where",
+    "expose_defaults",
+    "self_ty",
+    "also_impl_for",
+    "profile",
+    "const_default",
+    "rename_all",
+    "post",
+    "pinned",
+    "impl_default",
+    "singleton",
+    "from",
+    "marker",
+    "deref",
+    "default",
+    "builder",
+    "test_full",
+    "str_params",
+    "generics_on_fn",
+    "with_overrides",
+    "cfg",
+    "validate_fn",
+    "assert_invariants",
+    "wrap",
+    "returns",
+    "sorted_params",
+    "field_order_check",
+    "eager_default",
+    "as_tuple_args",
+    "from_map",
+    "params",
+    "copy_ctor",
+    "from_json",
+    "optional_params",
+    "zeroed",
+    "defaults_from",
+    "builder_into",
+    "ignore_unknown",
+    "typed_builder",
+    "checked_builder",
+    "prefix",
+    "expose_builder_fn",
+    "async",
+    "const",
+    "try_const",
+    "extern_c",
+    "resource",
+];
+
+/// Struct keys that `#[new(default)]` can be combined with.
+const DEFAULT_NEW_KEYS: &[&str] = &[
+    "default",
+    "private",
+    "no_must_use",
+    "inline",
+    "where",
+    "self_ty",
+    "cfg",
+    "const",
+    "try_const",
+    "ignore_unknown",
+];
+
+/// Keys accepted by the field-level `#[new(...)]` attribute.
+const FIELD_KEYS: &[&str] = &[
+    "group",
+    "rename",
+    "default",
+    "skip_if_default",
+    "try",
+    "from_iter",
+    "collect",
+    "lock",
+    "cell",
+    "nonzero",
+    "from_secs",
+    "from_millis",
+    "from_micros",
+    "from_nanos",
+    "unbox",
+    "clamp",
+    "intern",
+    "as_ref",
+    "param_ty",
+    "convert",
+    "init",
+    "no_str",
+    "flatten",
+    "nested_default",
+    "skip_default_bound",
+    "default_fn",
+    "non_empty",
+    "regex",
+    "param_attr",
+    "type_name",
+    "caller_location",
+    "timed",
+];
+
+/// Options collected from the struct-level `#[new(...)]` attributes.
+#[derive(Default)]
+pub(crate) struct StructAttrs {
+    /// Emit the constructor without a visibility qualifier.
+    pub private: bool,
+    /// Logging crate used to trace constructor calls, if enabled.
+    pub trace: Option<Ident>,
+    /// Name of a free function generated instead of the inherent `new` method.
+    pub free_fn: Option<Ident>,
+    /// Generate a fallible `try_new` returning `Result<Self, _>` instead of `new`.
+    pub try_new: bool,
+    /// Keep the name `new` for the fallible constructor, whose error defaults to `Infallible`.
+    pub fallible: bool,
+    /// Error type of the fallible constructor.
+    pub error: Option<Type>,
+    /// `#[inline]` hint for the constructor: `None` for no hint, `Some(None)` for a bare `#[inline]`,
+    /// `Some(Some(mode))` for `#[inline(mode)]`.
+    pub inline: Option<Option<Ident>>,
+    /// Omit `#[must_use]` from the constructor.
+    pub no_must_use: bool,
+    /// Document the fields the constructor defaults in a `# Defaults` section.
+    pub doc_defaults: bool,
+    /// Document the parameters and their types in an `# Arguments` section, along with the
+    /// `# Defaults` section.
+    pub doc_args: bool,
+    /// Generate `FIELD_COUNT` and `FIELD_NAMES` associated constants.
+    pub metadata: bool,
+    /// Generate a `macro_rules!` taking the parameters as `field: value` pairs.
+    pub named_args_macro: bool,
+    /// Extra predicates added to the where-clause of the generated impl.
+    pub where_predicates: Vec<WherePredicate>,
+    /// Expose literal field defaults as `<FIELD>_DEFAULT` associated constants.
+    pub expose_defaults: bool,
+    /// Type the generated impl is written for, instead of the struct itself.
+    pub self_ty: Option<Type>,
+    /// Type aliases, as `Alias = Type` pairs, naming concrete instantiations of the struct.
+    pub also_impl_for: Vec<(Ident, Type)>,
+    /// Presets from `#[new(profile(name = "...", defaults(...)))]`, as the name of the generated
+    /// function and the `field = value` pairs it uses.
+    pub profiles: Vec<(Ident, Vec<(Ident, Expr)>)>,
+    /// Generate a `const DEFAULT: Self` from the field defaults.
+    pub const_default: bool,
+    /// Naming convention applied to the parameter names, leaving the field names as they are.
+    pub rename_all: Option<Case>,
+    /// Expression evaluated on the constructed value, bound to `value`, before it is returned.
+    pub post: Option<Expr>,
+    /// Return the new value as `Pin<Box<Self>>`.
+    pub pinned: bool,
+    /// Also implement `Default` by calling the zero-parameter constructor.
+    pub impl_default: bool,
+    /// Generate `instance() -> &'static Self`, built once with the parameterless constructor.
+    pub singleton: bool,
+    /// Also implement `From<T>` by calling the constructor with its single parameter.
+    pub impl_from: bool,
+    /// Marker traits implemented, with an empty impl, for the struct.
+    pub markers: Vec<Path>,
+    /// Also implement `Deref` and `DerefMut` to the struct's only field.
+    pub deref: bool,
+    /// Generate `new()` as an alias of `Default::default()` instead of taking fields.
+    pub default_new: bool,
+    /// Generate `with_<field>` methods for the public fields that have a default.
+    pub builder: bool,
+    /// Generate a `#[cfg(test)]` `new_full` taking every field, private ones included.
+    pub test_full: bool,
+    /// Take `String` fields as `&str` parameters.
+    pub str_params: bool,
+    /// Move the type parameters only defaulted fields use from the impl onto the constructor.
+    pub generics_on_fn: bool,
+    /// Generate `with(base, ...)` taking the parameters of `new` and the other fields from `base`.
+    pub with_overrides: bool,
+    /// Configuration predicate the generated items are gated behind.
+    pub cfg: Option<Meta>,
+    /// Function checking the constructed value, returning `Result<(), E>`, called by `try_new`.
+    pub validate_fn: Option<Path>,
+    /// Method returning `bool` that `new` checks with `debug_assert!` on the constructed value.
+    pub assert_invariants: Option<Ident>,
+    /// Function the constructed value is passed through before it is returned.
+    pub wrap: Option<Path>,
+    /// Return type of the constructor when `wrap` is set.
+    pub returns: Option<Type>,
+    /// Order the constructor parameters alphabetically by field name.
+    pub sorted_params: bool,
+    /// Reject a parameter order that differs from the order the fields are declared in.
+    pub field_order_check: bool,
+    /// Hoist literal field defaults into hidden associated constants.
+    pub eager_default: bool,
+    /// Take every constructor parameter as one tuple parameter.
+    pub as_tuple_args: bool,
+    /// Generate `from_map` parsing the parameters from a map of field names to strings.
+    pub from_map: bool,
+    /// Fields taken as constructor parameters whatever their visibility, the others being
+    /// defaulted.
+    pub params: Option<Vec<Ident>>,
+    /// Generate `from_ref(&Self) -> Self` for `Copy` structs.
+    pub copy_ctor: bool,
+    /// Generate `from_json(&str)`, gated on the deriving crate's `serde` feature.
+    pub from_json: bool,
+    /// Take the public fields that have a default as `Option` parameters.
+    pub optional_params: bool,
+    /// Default fields through `bytemuck::Zeroable::zeroed()` instead of `Default::default()`.
+    pub zeroed: bool,
+    /// Constant whose like-named fields initialize the fields without a default of their own.
+    pub defaults_from: Option<ExprPath>,
+    /// Take the `with_<field>` setter arguments as `impl Into<T>`.
+    pub builder_into: bool,
+    /// Warn about unknown keys, in the struct and field attributes, instead of rejecting them.
+    pub ignore_unknown: bool,
+    /// Generate a type-state `<Struct>Builder` whose `build` requires every parameter.
+    pub typed_builder: bool,
+    /// Generate a `<Struct>Builder` whose `build` returns an error naming an unset parameter.
+    pub checked_builder: bool,
+    /// Generate the constructor as an `async fn`.
+    pub is_async: bool,
+    /// Generate the constructor as a `const fn`.
+    pub is_const: bool,
+    /// With `is_const`, fall back to a non-const constructor when it cannot be const.
+    pub try_const: bool,
+    /// Generate the constructor with the C ABI, as an `extern "C" fn`.
+    pub extern_c: bool,
+    /// Prefix of the generated type names, such as `Internal` for `InternalServerBuilder`.
+    pub prefix: Option<Ident>,
+    /// Name of the function starting a builder, in place of `builder`.
+    pub builder_fn: Option<Ident>,
+    /// Extra leading parameters, as `name: Type` pairs, that field defaults can use, such as an
+    /// arena to allocate from.
+    pub resources: Vec<(Ident, Type)>,
+    /// Unknown keys skipped under `ignore_unknown`.
+    pub ignored: Vec<Path>,
+}
+
+impl StructAttrs {
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+        // Looked up ahead so that unknown keys listed before `ignore_unknown` are skipped as well.
+        let mut out = Self {
+            ignore_unknown: ignores_unknown(attrs),
+            ..Self::default()
+        };
+        // Every key given, for the options that `default` does not support.
+        let mut keys = Vec::new();
+
+        for attr in attrs.iter().filter(|a| is_new_attr(a)) {
+            attr.parse_nested_meta(|meta| {
+                if is_known(&meta, STRUCT_KEYS) {
+                    keys.extend(meta.path.get_ident().cloned());
+                }
+                if meta.path.is_ident("private") {
+                    out.private = true;
+                    Ok(())
+                } else if meta.path.is_ident("trace") {
+                    let krate = if meta.input.peek(Token![=]) {
+                        let lit: LitStr = meta.value()?.parse()?;
+                        match lit.value().as_str() {
+                            "log" | "tracing" => Ident::new(&lit.value(), lit.span()),
+                            _ => {
+                                return Err(syn::Error::new_spanned(
+                                    lit,
+                                    "expected `log` or `tracing` as the trace crate",
+                                ))
+                            }
+                        }
+                    } else {
+                        Ident::new("log", meta.path.span())
+                    };
+                    out.trace = Some(krate);
+                    Ok(())
+                } else if meta.path.is_ident("free_fn") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.free_fn = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("try_new") {
+                    out.try_new = true;
+                    Ok(())
+                } else if meta.path.is_ident("fallible") {
+                    out.fallible = true;
+                    Ok(())
+                } else if meta.path.is_ident("inline") {
+                    let mode = if meta.input.peek(Token![=]) {
+                        let lit: LitStr = meta.value()?.parse()?;
+                        match lit.value().as_str() {
+                            "always" | "never" => Some(Ident::new(&lit.value(), lit.span())),
+                            _ => {
+                                return Err(syn::Error::new_spanned(
+                                    lit,
+                                    "expected `always` or `never` as the inline mode",
+                                ))
+                            }
+                        }
+                    } else {
+                        None
+                    };
+                    out.inline = Some(mode);
+                    Ok(())
+                } else if meta.path.is_ident("no_must_use") {
+                    out.no_must_use = true;
+                    Ok(())
+                } else if meta.path.is_ident("hot") {
+                    out.inline = Some(Some(Ident::new("always", meta.path.span())));
+                    out.no_must_use = true;
+                    Ok(())
+                } else if meta.path.is_ident("doc_args") {
+                    out.doc_args = true;
+                    Ok(())
+                } else if meta.path.is_ident("doc_defaults") {
+                    out.doc_defaults = true;
+                    Ok(())
+                } else if meta.path.is_ident("metadata") {
+                    out.metadata = true;
+                    Ok(())
+                } else if meta.path.is_ident("macro") {
+                    out.named_args_macro = true;
+                    Ok(())
+                } else if meta.path.is_ident("where") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    let predicates =
+                        lit.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+                    out.where_predicates.extend(predicates);
+                    Ok(())
+                } else if meta.path.is_ident("expose_defaults") {
+                    out.expose_defaults = true;
+                    Ok(())
+                } else if meta.path.is_ident("profile") {
+                    let mut name = None;
+                    let mut defaults = Vec::new();
+                    meta.parse_nested_meta(|inner| {
+                        if inner.path.is_ident("name") {
+                            let lit: LitStr = inner.value()?.parse()?;
+                            name = Some(lit.parse::<Ident>().map_err(|_| {
+                                syn::Error::new_spanned(&lit, "`name` expects a function name")
+                            })?);
+                            Ok(())
+                        } else if inner.path.is_ident("defaults") {
+                            let content;
+                            parenthesized!(content in inner.input);
+                            let values =
+                                Punctuated::<NamedValue, Token![,]>::parse_terminated(&content)?;
+                            defaults.extend(values.into_iter().map(|v| (v.name, v.value)));
+                            Ok(())
+                        } else {
+                            Err(inner.error("expected `name` or `defaults`"))
+                        }
+                    })?;
+                    let name = name.ok_or_else(|| {
+                        syn::Error::new_spanned(&meta.path, "`profile` requires `name = \"...\"`")
+                    })?;
+                    out.profiles.push((name, defaults));
+                    Ok(())
+                } else if meta.path.is_ident("also_impl_for") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    let alias: TypeAlias = lit.parse().map_err(|_| {
+                        syn::Error::new_spanned(
+                            &lit,
+                            "`also_impl_for` expects `Alias = Type`, such as `\"FooU32 = Foo<u32>\"`",
+                        )
+                    })?;
+                    out.also_impl_for.push((alias.name, alias.ty));
+                    Ok(())
+                } else if meta.path.is_ident("self_ty") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.self_ty = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("const_default") {
+                    out.const_default = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename_all") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    let Some(case) = Case::from_name(&lit.value()) else {
+                        let expected = Case::NAMES
+                            .iter()
+                            .map(|name| format!("`{name}`"))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            format!("expected one of {expected} as the `rename_all` case"),
+                        ));
+                    };
+                    out.rename_all = Some(case);
+                    Ok(())
+                } else if meta.path.is_ident("post") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.post = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("pinned") {
+                    out.pinned = true;
+                    Ok(())
+                } else if meta.path.is_ident("impl_default") {
+                    out.impl_default = true;
+                    Ok(())
+                } else if meta.path.is_ident("singleton") {
+                    out.singleton = true;
+                    Ok(())
+                } else if meta.path.is_ident("from") {
+                    out.impl_from = true;
+                    Ok(())
+                } else if meta.path.is_ident("marker") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.markers.push(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("deref") {
+                    out.deref = true;
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    out.default_new = true;
+                    Ok(())
+                } else if meta.path.is_ident("builder") {
+                    out.builder = true;
+                    Ok(())
+                } else if meta.path.is_ident("test_full") {
+                    out.test_full = true;
+                    Ok(())
+                } else if meta.path.is_ident("str_params") {
+                    out.str_params = true;
+                    Ok(())
+                } else if meta.path.is_ident("generics_on_fn") {
+                    out.generics_on_fn = true;
+                    Ok(())
+                } else if meta.path.is_ident("with_overrides") {
+                    out.with_overrides = true;
+                    Ok(())
+                } else if meta.path.is_ident("cfg") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.cfg = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("validate_fn") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.validate_fn = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("assert_invariants") {
+                    let method = if meta.input.peek(Token![=]) {
+                        let lit: LitStr = meta.value()?.parse()?;
+                        lit.parse()?
+                    } else {
+                        Ident::new("invariants", meta.path.span())
+                    };
+                    out.assert_invariants = Some(method);
+                    Ok(())
+                } else if meta.path.is_ident("wrap") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.wrap = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("returns") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.returns = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("sorted_params") {
+                    out.sorted_params = true;
+                    Ok(())
+                } else if meta.path.is_ident("field_order_check") {
+                    out.field_order_check = true;
+                    Ok(())
+                } else if meta.path.is_ident("eager_default") {
+                    out.eager_default = true;
+                    Ok(())
+                } else if meta.path.is_ident("as_tuple_args") {
+                    out.as_tuple_args = true;
+                    Ok(())
+                } else if meta.path.is_ident("from_map") {
+                    out.from_map = true;
+                    Ok(())
+                } else if meta.path.is_ident("copy_ctor") {
+                    out.copy_ctor = true;
+                    Ok(())
+                } else if meta.path.is_ident("from_json") {
+                    if !cfg!(feature = "serde") {
+                        return Err(meta.error(
+                            "`from_json` requires the `serde` feature of `impl-new-derive`",
+                        ));
+                    }
+                    out.from_json = true;
+                    Ok(())
+                } else if meta.path.is_ident("optional_params") {
+                    out.optional_params = true;
+                    Ok(())
+                } else if meta.path.is_ident("zeroed") {
+                    if !cfg!(feature = "bytemuck") {
+                        return Err(meta.error(
+                            "`zeroed` requires the `bytemuck` feature of `impl-new-derive`",
+                        ));
+                    }
+                    out.zeroed = true;
+                    Ok(())
+                } else if meta.path.is_ident("defaults_from") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.defaults_from = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("async") {
+                    out.is_async = true;
+                    Ok(())
+                } else if meta.path.is_ident("resource") {
+                    let content;
+                    parenthesized!(content in meta.input);
+                    let members = Punctuated::<TypedParam, Token![,]>::parse_terminated(&content)?;
+                    out.resources
+                        .extend(members.into_iter().map(|m| (m.name, m.ty)));
+                    Ok(())
+                } else if meta.path.is_ident("const") {
+                    out.is_const = true;
+                    Ok(())
+                } else if meta.path.is_ident("try_const") {
+                    out.try_const = true;
+                    Ok(())
+                } else if meta.path.is_ident("extern_c") {
+                    out.extern_c = true;
+                    Ok(())
+                } else if meta.path.is_ident("typed_builder") {
+                    out.typed_builder = true;
+                    Ok(())
+                } else if meta.path.is_ident("checked_builder") {
+                    out.checked_builder = true;
+                    Ok(())
+                } else if meta.path.is_ident("prefix") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.prefix = Some(lit.parse().map_err(|_| {
+                        syn::Error::new_spanned(&lit, "`prefix` expects the start of a type name")
+                    })?);
+                    Ok(())
+                } else if meta.path.is_ident("expose_builder_fn") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.builder_fn = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("ignore_unknown") {
+                    Ok(())
+                } else if meta.path.is_ident("builder_into") {
+                    out.builder_into = true;
+                    Ok(())
+                } else if meta.path.is_ident("params") {
+                    let content;
+                    parenthesized!(content in meta.input);
+                    let names = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+                    out.params = Some(names.into_iter().collect());
+                    Ok(())
+                } else if meta.path.is_ident("error") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.error = Some(lit.parse()?);
+                    Ok(())
+                } else if out.ignore_unknown && !is_known(&meta, FIELD_KEYS) {
+                    skip_value(&meta)?;
+                    out.ignored.push(meta.path.clone());
+                    Ok(())
+                } else {
+                    Err(unknown_key(&meta, STRUCT_KEYS, FIELD_KEYS, "field"))
+                }
+            })?;
+        }
+
+        // `new()` built from `Default` only takes the options that shape its signature.
+        if out.default_new {
+            let unsupported = keys
+                .iter()
+                .find(|key| !DEFAULT_NEW_KEYS.iter().any(|supported| *key == supported));
+            if let Some(key) = unsupported {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    format!("`default` on the struct cannot be combined with `{key}`"),
+                ));
+            }
+        }
+        // `fallible` is `try_new` under the name `new`, so everything else treats it as such.
+        if out.fallible {
+            if out.try_new {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "`fallible` and `try_new` cannot be combined; `fallible` already returns a \
+                     `Result` from `new`",
+                ));
+            }
+            out.try_new = true;
+        }
+        if out.typed_builder && (out.str_params || out.wrap.is_some() || out.generics_on_fn) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`typed_builder` cannot be combined with `str_params`, `wrap` or `generics_on_fn`",
+            ));
+        }
+        if out.checked_builder
+            && (out.typed_builder
+                || out.try_new
+                || out.str_params
+                || out.wrap.is_some()
+                || out.generics_on_fn)
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`checked_builder` cannot be combined with `typed_builder`, `try_new`, \
+                 `fallible`, `str_params`, `wrap` or `generics_on_fn`",
+            ));
+        }
+        if !out.resources.is_empty()
+            && (out.from_map
+                || out.with_overrides
+                || out.typed_builder
+                || out.checked_builder
+                || out.named_args_macro
+                || out.default_new)
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`resource` cannot be combined with `from_map`, `with_overrides`, \
+                 `typed_builder`, `checked_builder`, `macro` or `default`, which do not take the \
+                 resource parameters",
+            ));
+        }
+        if let (true, Some(defaults)) = (out.zeroed, &out.defaults_from) {
+            return Err(syn::Error::new_spanned(
+                defaults,
+                "`defaults_from` and `zeroed` cannot be combined",
+            ));
+        }
+        if let (false, false, Some(prefix)) = (out.typed_builder, out.checked_builder, &out.prefix)
+        {
+            return Err(syn::Error::new_spanned(
+                prefix,
+                "`prefix` requires `typed_builder` or `checked_builder`, which generate the types \
+                 it names",
+            ));
+        }
+        if let (false, false, Some(builder_fn)) =
+            (out.typed_builder, out.checked_builder, &out.builder_fn)
+        {
+            return Err(syn::Error::new_spanned(
+                builder_fn,
+                "`expose_builder_fn` requires `typed_builder` or `checked_builder`",
+            ));
+        }
+        if out.extern_c && out.is_async {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`extern_c` and `async` cannot be combined",
+            ));
+        }
+        if out.try_const && !out.is_const {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`try_const` requires `#[new(const)]`",
+            ));
+        }
+        if out.builder_into && !out.builder {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`builder_into` requires `#[new(builder)]`",
+            ));
+        }
+        if let (false, Some(validate)) = (out.try_new, &out.validate_fn) {
+            return Err(syn::Error::new_spanned(
+                validate,
+                "`validate_fn` requires `#[new(try_new)]`",
+            ));
+        }
+        match (&out.wrap, &out.returns) {
+            (Some(wrap), None) => {
+                return Err(syn::Error::new_spanned(
+                    wrap,
+                    "`wrap` needs `returns = \"...\"` naming the wrapped type, \
+                     since a function's return type cannot be inferred",
+                ))
+            }
+            (None, Some(returns)) => {
+                return Err(syn::Error::new_spanned(
+                    returns,
+                    "`returns` requires `wrap`",
+                ))
+            }
+            (Some(wrap), Some(_)) if out.pinned => {
+                return Err(syn::Error::new_spanned(
+                    wrap,
+                    "`wrap` and `pinned` cannot be combined",
+                ))
+            }
+            _ => {}
+        }
+        if let (false, Some(error)) = (out.try_new, &out.error) {
+            return Err(syn::Error::new_spanned(
+                error,
+                "`error` requires `#[new(try_new)]`",
+            ));
+        }
+
+        Ok(out)
+    }
+}
+
+/// `Duration` constructors a field can be built with, each taking a `u64` count of the unit.
+const DURATION_UNITS: &[&str] = &["from_secs", "from_millis", "from_micros", "from_nanos"];
+
+/// Options collected from the field-level `#[new(...)]` attributes.
+#[derive(Default)]
+pub(crate) struct FieldAttrs {
+    /// Name of the tuple parameter this field is passed through.
+    pub group: Option<LitStr>,
+    /// Expression from `#[default(...)]` used instead of `Default::default()`.
+    pub default: Option<Expr>,
+    /// Accept any `IntoIterator` and collect it into the field.
+    pub from_iter: bool,
+    /// Initialize a public field with `Default::default()` instead of taking it as a parameter.
+    pub skip_if_default: bool,
+    /// Take the parameter as a `Result` and propagate its error from `try_new`.
+    pub try_param: bool,
+    /// Take the inner value of a `Mutex<T>` or `RwLock<T>` field and wrap it in the lock.
+    pub lock: bool,
+    /// Take the inner value of a `Cell<T>` or `RefCell<T>` field and wrap it in the cell.
+    pub cell: bool,
+    /// Take the raw integer of a `NonZero*` field and check it is non-zero.
+    pub nonzero: bool,
+    /// `Duration` constructor, such as `from_millis`, building the field from a `u64` count.
+    pub duration: Option<&'static str>,
+    /// Take the field's value as a `Box<T>` and move it out of the box.
+    pub unbox: bool,
+    /// Inclusive range the parameter is clamped into.
+    pub clamp: Option<ExprRange>,
+    /// Struct `resource` the `&str` parameter is interned with, through its `intern` method.
+    pub intern: Option<Ident>,
+    /// Target of the `impl AsRef<T>` parameter, whose referent is stored with `to_owned`.
+    pub as_ref: Option<Type>,
+    /// Type of the parameter, when it differs from the field's type.
+    pub param_ty: Option<Type>,
+    /// Expression converting the parameter, referenced by name, into the field's value.
+    pub convert: Option<Expr>,
+    /// Initialize the field by calling a closure parameter: `Some(None)` names the parameter
+    /// `<field>_init`, `Some(Some(name))` uses the given name.
+    pub init: Option<Option<Ident>>,
+    /// Keep taking this `String` field as a `String` under `#[new(str_params)]`.
+    pub no_str: bool,
+    /// Parameters, as `name: Type` pairs, passed on to the field type's own `new`.
+    pub flatten: Option<Vec<(Ident, Type)>>,
+    /// Reject an empty collection, panicking in `new` and returning an error from `try_new`.
+    pub non_empty: bool,
+    /// Pattern the string parameter must match, checked with the `regex` crate.
+    pub regex: Option<LitStr>,
+    /// Attributes put on the constructor parameter, e.g. `allow(unused_variables)`.
+    pub param_attrs: Vec<Meta>,
+    /// Leave this field's type out of the `Default` bounds added for bare type parameters.
+    pub skip_default_bound: bool,
+    /// Initialize the field with the name of the struct.
+    pub type_name: bool,
+    /// Initialize the field with the location `new` was called from.
+    pub caller_location: bool,
+    /// Initialize the `Duration` field with the time the constructor took.
+    pub timed: bool,
+    /// Name of the constructor parameter, in place of the field's name.
+    pub rename: Option<Ident>,
+    /// The deprecated `#[default(...)]` attribute, kept to warn about it.
+    pub legacy_default: Option<Attribute>,
+    /// Unknown keys skipped under the struct's `ignore_unknown`.
+    pub ignored: Vec<Path>,
+    /// Every known key given, for the struct-level `default`, which only reads `default`.
+    pub keys: Vec<Ident>,
+}
+
+impl FieldAttrs {
+    pub fn from_field(field: &Field, ignore_unknown: bool) -> Result<Self> {
+        let mut out = Self {
+            default: extract_default_value(&field.attrs)?,
+            legacy_default: field
+                .attrs
+                .iter()
+                .find(|a| a.path().is_ident("default"))
+                .cloned(),
+            ..Self::default()
+        };
+
+        for attr in field.attrs.iter().filter(|a| is_new_attr(a)) {
+            attr.parse_nested_meta(|meta| {
+                if is_known(&meta, FIELD_KEYS) {
+                    out.keys.extend(meta.path.get_ident().cloned());
+                }
+                if meta.path.is_ident("group") {
+                    out.group = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    if meta.input.peek(token::Paren) {
+                        if out.default.is_some() {
+                            return Err(meta.error("duplicate `default` attribute"));
+                        }
+                        let content;
+                        parenthesized!(content in meta.input);
+                        out.default = Some(parse_default_expr(&content)?);
+                    } else {
+                        out.skip_if_default = true;
+                    }
+                    Ok(())
+                } else if meta.path.is_ident("skip_if_default") {
+                    out.skip_if_default = true;
+                    Ok(())
+                } else if meta.path.is_ident("try") {
+                    out.try_param = true;
+                    Ok(())
+                } else if meta.path.is_ident("from_iter") || meta.path.is_ident("collect") {
+                    out.from_iter = true;
+                    Ok(())
+                } else if meta.path.is_ident("lock") {
+                    out.lock = true;
+                    Ok(())
+                } else if meta.path.is_ident("cell") {
+                    out.cell = true;
+                    Ok(())
+                } else if meta.path.is_ident("nonzero") {
+                    out.nonzero = true;
+                    Ok(())
+                } else if let Some(unit) =
+                    DURATION_UNITS.iter().find(|unit| meta.path.is_ident(*unit))
+                {
+                    if let Some(previous) = out.duration {
+                        return Err(
+                            meta.error(format!("`{previous}` and `{unit}` cannot be combined"))
+                        );
+                    }
+                    out.duration = Some(*unit);
+                    Ok(())
+                } else if meta.path.is_ident("unbox") {
+                    out.unbox = true;
+                    Ok(())
+                } else if meta.path.is_ident("clamp") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    let expected = "`clamp` expects an inclusive range such as `\"0..=100\"`, \
+                                    `\"1..\"` or `\"..=9\"`";
+                    let range: ExprRange = lit
+                        .parse()
+                        .map_err(|_| syn::Error::new_spanned(&lit, expected))?;
+                    let exclusive =
+                        matches!(range.limits, RangeLimits::HalfOpen(_)) && range.end.is_some();
+                    if exclusive || (range.start.is_none() && range.end.is_none()) {
+                        return Err(syn::Error::new_spanned(&lit, expected));
+                    }
+                    out.clamp = Some(range);
+                    Ok(())
+                } else if meta.path.is_ident("intern") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.intern = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("as_ref") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.as_ref = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("param_ty") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.param_ty = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("convert") {
+                    let content;
+                    parenthesized!(content in meta.input);
+                    out.convert = Some(content.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("caller_location") {
+                    out.caller_location = true;
+                    Ok(())
+                } else if meta.path.is_ident("timed") {
+                    out.timed = true;
+                    Ok(())
+                } else if meta.path.is_ident("type_name") {
+                    out.type_name = true;
+                    Ok(())
+                } else if meta.path.is_ident("skip_default_bound") {
+                    out.skip_default_bound = true;
+                    Ok(())
+                } else if meta.path.is_ident("non_empty") {
+                    out.non_empty = true;
+                    Ok(())
+                } else if meta.path.is_ident("regex") {
+                    if !cfg!(feature = "regex") {
+                        return Err(
+                            meta.error("`regex` requires the `regex` feature of `impl-new-derive`")
+                        );
+                    }
+                    out.regex = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("param_attr") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.param_attrs
+                        .extend(lit.parse_with(Punctuated::<Meta, Token![,]>::parse_terminated)?);
+                    Ok(())
+                } else if meta.path.is_ident("nested_default") {
+                    if out.default.is_some() {
+                        return Err(meta.error("`nested_default` and `default` cannot be combined"));
+                    }
+                    let ty = &field.ty;
+                    out.default = Some(syn::parse_quote_spanned! {ty.span()=> <#ty>::new() });
+                    Ok(())
+                } else if meta.path.is_ident("default_fn") {
+                    if out.default.is_some() {
+                        return Err(meta.error("`default_fn` and `default` cannot be combined"));
+                    }
+                    let lit: LitStr = meta.value()?.parse()?;
+                    let path: ExprPath = lit.parse().map_err(|_| {
+                        syn::Error::new_spanned(
+                            &lit,
+                            "`default_fn` expects a function path such as `Vec::new`; \
+                             use `default(...)` for other expressions",
+                        )
+                    })?;
+                    out.default = Some(syn::parse_quote_spanned! {lit.span()=> #path() });
+                    Ok(())
+                } else if meta.path.is_ident("no_str") {
+                    out.no_str = true;
+                    Ok(())
+                } else if meta.path.is_ident("flatten") {
+                    let content;
+                    parenthesized!(content in meta.input);
+                    let members = Punctuated::<TypedParam, Token![,]>::parse_terminated(&content)?;
+                    out.flatten = Some(members.into_iter().map(|m| (m.name, m.ty)).collect());
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.rename = Some(param_ident(&lit)?);
+                    Ok(())
+                } else if meta.path.is_ident("init") {
+                    let name = if meta.input.peek(Token![=]) {
+                        let lit: LitStr = meta.value()?.parse()?;
+                        Some(lit.parse()?)
+                    } else {
+                        None
+                    };
+                    out.init = Some(name);
+                    Ok(())
+                } else if ignore_unknown && !is_known(&meta, STRUCT_KEYS) {
+                    skip_value(&meta)?;
+                    out.ignored.push(meta.path.clone());
+                    Ok(())
+                } else {
+                    Err(unknown_key(&meta, FIELD_KEYS, STRUCT_KEYS, "struct"))
+                }
+            })?;
+        }
+
+        if out.flatten.is_some() && (out.default.is_some() || out.skip_if_default) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`flatten` and `default` cannot be combined",
+            ));
+        }
+        if out.type_name && (out.default.is_some() || out.skip_if_default) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`type_name` and `default` cannot be combined",
+            ));
+        }
+        if out.caller_location && (out.default.is_some() || out.skip_if_default || out.type_name) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`caller_location` cannot be combined with `default` or `type_name`",
+            ));
+        }
+        if out.timed
+            && (out.default.is_some()
+                || out.skip_if_default
+                || out.type_name
+                || out.caller_location)
+        {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`timed` cannot be combined with `default`, `type_name` or `caller_location`",
+            ));
+        }
+        if out.rename.is_some() && (out.init.is_some() || out.flatten.is_some()) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`rename` cannot be combined with `init` or `flatten`, which name their own \
+                 parameters",
+            ));
+        }
+        if out.init.is_some() && (out.default.is_some() || out.skip_if_default) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`init` and `default` cannot be combined",
+            ));
+        }
+
+        let conversions = out.conversions();
+        if let [first, second, ..] = conversions.as_slice() {
+            return Err(syn::Error::new_spanned(
+                field,
+                format!("`{first}` and `{second}` cannot be combined"),
+            ));
+        }
+
+        Ok(out)
+    }
+
+    /// Names of the options that change how the parameter is converted into the field.
+    pub fn conversions(&self) -> Vec<&'static str> {
+        [
+            (self.from_iter, "from_iter"),
+            (self.try_param, "try"),
+            (self.lock, "lock"),
+            (self.cell, "cell"),
+            (self.nonzero, "nonzero"),
+            (self.duration.is_some(), self.duration.unwrap_or_default()),
+            (self.unbox, "unbox"),
+            (self.clamp.is_some(), "clamp"),
+            (self.intern.is_some(), "intern"),
+            (self.as_ref.is_some(), "as_ref"),
+            (self.param_ty.is_some(), "param_ty"),
+            (self.convert.is_some() && self.param_ty.is_none(), "convert"),
+            (self.init.is_some(), "init"),
+            (self.flatten.is_some(), "flatten"),
+        ]
+        .into_iter()
+        .filter_map(|(set, name)| set.then_some(name))
+        .collect()
+    }
+}
+
+/// One `name: Type` parameter of `#[new(flatten(...))]` or `#[new(resource(...))]`.
+struct TypedParam {
+    name: Ident,
+    ty: Type,
+}
+
+impl Parse for TypedParam {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        Ok(Self { name, ty })
+    }
+}
+
+/// The `Alias = Type` of `#[new(also_impl_for = "...")]`.
+struct TypeAlias {
+    name: Ident,
+    ty: Type,
+}
+
+impl Parse for TypeAlias {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let ty = input.parse()?;
+        Ok(Self { name, ty })
+    }
+}
+
+/// One `field = value` pair of a profile's `defaults(...)`.
+struct NamedValue {
+    name: Ident,
+    value: Expr,
+}
+
+impl Parse for NamedValue {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+        Ok(Self { name, value })
+    }
+}
+
+/// Returns the expression of the deprecated `#[default(...)]` attribute, if the field has one.
+pub(crate) fn extract_default_value(attrs: &[Attribute]) -> Result<Option<Expr>> {
+    let mut default = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("default")) {
+        if default.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "duplicate `default` attribute",
+            ));
+        }
+        let expr = attr.parse_args_with(parse_default_expr).map_err(|err| {
+            let mut error =
+                syn::Error::new_spanned(attr, "expected a single expression in `#[default(...)]`");
+            error.combine(err);
+            error
+        })?;
+        default = Some(expr);
+    }
+    Ok(default)
+}
+
+/// Parses the contents of `default(...)`, which must be exactly one expression.
+fn parse_default_expr(input: ParseStream) -> Result<Expr> {
+    let values = Punctuated::<Expr, Token![,]>::parse_terminated(input)?;
+    match values.len() {
+        1 => Ok(values.into_iter().next().unwrap()),
+        0 => Err(input.error("expected an expression")),
+        _ => Err(syn::Error::new_spanned(
+            values,
+            "wrap tuple defaults in parentheses, e.g. `default((1, 2))`",
+        )),
+    }
+}
+
+/// Returns `true` for `#[new(...)]` and its namespaced alias `#[impl_new(...)]`, which other
+/// derives are unlikely to claim or strip.
+fn is_new_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("new") || attr.path().is_ident("impl_new")
+}
+
+/// Returns `true` if a struct-level `#[new(...)]` attribute sets `ignore_unknown`.
+fn ignores_unknown(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|a| is_new_attr(a))
+        .filter_map(|a| a.meta.require_list().ok())
+        .flat_map(|list| list.tokens.clone())
+        .any(|token| matches!(token, TokenTree::Ident(ident) if ident == "ignore_unknown"))
+}
+
+/// Returns `true` if the key is one of `keys`.
+fn is_known(meta: &ParseNestedMeta, keys: &[&str]) -> bool {
+    meta.path
+        .get_ident()
+        .is_some_and(|ident| keys.iter().any(|key| ident == key))
+}
+
+/// Consumes the `= value` or `(...)` following an ignored key, if any.
+fn skip_value(meta: &ParseNestedMeta) -> Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(token::Paren) {
+        let content;
+        parenthesized!(content in meta.input);
+        content.parse::<TokenStream>()?;
+    }
+    Ok(())
+}
+
+/// Builds the error for an unknown `#[new(...)]` key, suggesting the closest valid one.
+///
+/// `other` holds the keys of the other attribute position, so that a struct option used on a
+/// field (or the reverse) is reported as misplaced rather than unknown.
+fn unknown_key(
+    meta: &ParseNestedMeta,
+    keys: &[&str],
+    other: &[&str],
+    other_kind: &str,
+) -> syn::Error {
+    let key = meta
+        .path
+        .get_ident()
+        .map(Ident::to_string)
+        .unwrap_or_else(|| docs::source_text(&meta.path));
+
+    if other.contains(&key.as_str()) {
+        return meta.error(format!("`{key}` is a {other_kind}-level `new` option"));
+    }
+
+    let expected = keys
+        .iter()
+        .map(|key| format!("`{key}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let suggestion = keys
+        .iter()
+        .map(|candidate| (edit_distance(&key, candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= candidate.len().max(3) / 3)
+        .min_by_key(|(distance, _)| *distance);
+
+    match suggestion {
+        Some((_, candidate)) => meta.error(format!(
+            "unknown `new` option `{key}`, did you mean `{candidate}`?\nexpected one of: {expected}"
+        )),
+        None => meta.error(format!(
+            "unknown `new` option `{key}`\nexpected one of: {expected}"
+        )),
+    }
+}
+
+/// Levenshtein distance between two keys.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = prev + usize::from(ca != cb);
+            prev = row[j + 1];
+            row[j + 1] = substitute.min(prev + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Parses a parameter name, using the raw form for keywords such as `"type"`.
+fn param_ident(lit: &LitStr) -> Result<Ident> {
+    let value = lit.value();
+    syn::parse_str::<Ident>(&value)
+        .or_else(|_| syn::parse_str::<Ident>(&format!("r#{value}")))
+        .map(|mut ident| {
+            ident.set_span(lit.span());
+            ident
+        })
+        .map_err(|_| syn::Error::new_spanned(lit, "`rename` expects a parameter name"))
+}
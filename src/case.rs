@@ -0,0 +1,103 @@
+/// Splits an identifier into lowercase words at underscores and case boundaries.
+fn words(ident: &str) -> Vec<String> {
+    let chars = ident.chars().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+/// Converts an identifier such as `HttpServer` to `http_server`.
+pub(crate) fn to_snake_case(ident: &str) -> String {
+    words(ident).join("_")
+}
+
+/// Converts an identifier such as `request_timeout` to `REQUEST_TIMEOUT`.
+pub(crate) fn to_screaming_snake_case(ident: &str) -> String {
+    to_snake_case(ident).to_uppercase()
+}
+
+/// Converts an identifier such as `request_timeout` to `RequestTimeout`.
+pub(crate) fn to_pascal_case(ident: &str) -> String {
+    words(ident)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Converts an identifier such as `request_timeout` to `requestTimeout`.
+pub(crate) fn to_camel_case(ident: &str) -> String {
+    let pascal = to_pascal_case(ident);
+    let mut chars = pascal.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Naming convention selected with `#[new(rename_all = "...")]`.
+#[derive(Clone, Copy)]
+pub(crate) enum Case {
+    Snake,
+    Camel,
+    Pascal,
+    ScreamingSnake,
+}
+
+impl Case {
+    /// Names accepted by `rename_all`, in the spelling serde uses.
+    pub const NAMES: &'static [&'static str] = &[
+        "snake_case",
+        "camelCase",
+        "PascalCase",
+        "SCREAMING_SNAKE_CASE",
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "snake_case" => Some(Case::Snake),
+            "camelCase" => Some(Case::Camel),
+            "PascalCase" => Some(Case::Pascal),
+            "SCREAMING_SNAKE_CASE" => Some(Case::ScreamingSnake),
+            _ => None,
+        }
+    }
+
+    pub fn apply(self, ident: &str) -> String {
+        match self {
+            Case::Snake => to_snake_case(ident),
+            Case::Camel => to_camel_case(ident),
+            Case::Pascal => to_pascal_case(ident),
+            Case::ScreamingSnake => to_screaming_snake_case(ident),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::case;
+use crate::expand::Param;
+use crate::typed_builder::{declared_params, generic_args, impl_params};
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{Generics, Visibility};
+
+/// Generates a runtime-checked builder for the constructor, named `<Struct>Builder`, and the
+/// `<Struct>BuilderError` it reports unset parameters with.
+///
+/// Unlike the [`TypedBuilder`](crate::typed_builder::TypedBuilder), the builder is a single type
+/// holding an `Option` per constructor argument, so it can be passed around and filled in
+/// conditionally. `build` returns `Err` with the variant of the first parameter, in constructor
+/// order, that was not set, and otherwise forwards the arguments to `ctor`.
+pub(crate) struct CheckedBuilder<'a> {
+    pub name: &'a Ident,
+    /// Prepended to the names of the generated types, from `#[new(prefix = "...")]`.
+    pub prefix: &'a str,
+    /// Name of the entry point, `builder` unless set with `#[new(expose_builder_fn = "...")]`.
+    pub entry_fn: &'a Ident,
+    pub struct_vis: &'a Visibility,
+    pub vis: &'a TokenStream,
+    pub generics: &'a Generics,
+    pub target: &'a TokenStream,
+    pub ctor: &'a TokenStream,
+    pub output: &'a TokenStream,
+    /// Whether the constructor is an `async fn`, which makes `build` one too.
+    pub is_async: bool,
+    pub params: &'a [Param<'a>],
+}
+
+impl CheckedBuilder<'_> {
+    /// The builder's name, e.g. `ServerBuilder`, or `InternalServerBuilder` with a prefix.
+    pub fn ident(&self) -> Ident {
+        format_ident!("{}{}Builder", self.prefix, self.name)
+    }
+
+    /// The error's name, e.g. `ServerBuilderError`.
+    pub fn error_ident(&self) -> Ident {
+        format_ident!("{}{}BuilderError", self.prefix, self.name)
+    }
+
+    /// The `builder()` entry point, or the one named by `entry_fn`, placed in the struct's
+    /// inherent impl.
+    pub fn entry(&self) -> TokenStream {
+        let builder = self.ident();
+        let entry_fn = self.entry_fn;
+        let vis = self.vis;
+        let args = generic_args(self.generics);
+        let doc = format!(" Starts an empty [`{builder}`].");
+        quote! {
+            #[doc = #doc]
+            #vis fn #entry_fn() -> #builder<#(#args),*> {
+                ::core::default::Default::default()
+            }
+        }
+    }
+
+    /// The builder struct, its setters and `build`, and the error enum.
+    pub fn items(&self) -> TokenStream {
+        let builder = self.ident();
+        let error = self.error_ident();
+        let struct_vis = self.struct_vis;
+        let vis = self.vis;
+        let target = self.target;
+        let names = self
+            .params
+            .iter()
+            .flat_map(Param::names)
+            .collect::<Vec<_>>();
+        let tys = self.params.iter().flat_map(Param::tys).collect::<Vec<_>>();
+        let variants = names
+            .iter()
+            .map(|name| format_ident!("Missing{}", case::to_pascal_case(&name.unraw().to_string())))
+            .collect::<Vec<_>>();
+        let messages = names
+            .iter()
+            .map(|name| format!("`{}` was not set", name.unraw()))
+            .collect::<Vec<_>>();
+        let args = generic_args(self.generics);
+        let decl_params = declared_params(self.generics);
+        let impl_params = impl_params(self.generics);
+        let where_clause = &self.generics.where_clause;
+
+        let mut fields = names.iter();
+        let call_args = self.params.iter().map(|param| {
+            let members = param
+                .names()
+                .iter()
+                .map(|_| fields.next().expect("builder field"))
+                .collect::<Vec<_>>();
+            match param {
+                Param::Single(_) => quote! { #(#members)* },
+                Param::Group { .. } => quote! { (#(#members,)*) },
+            }
+        });
+        let ctor = self.ctor;
+        let output = self.output;
+        let (asyncness, await_ctor) = if self.is_async {
+            (quote! { async }, quote! { .await })
+        } else {
+            (quote! {}, quote! {})
+        };
+
+        let builder_doc = format!(
+            " Builder for [`{}`], returned by `{}::{}()`.",
+            self.name, self.name, self.entry_fn
+        );
+        let error_doc = format!(" A parameter that was not set on a [`{builder}`].");
+        let variant_docs = messages.iter().map(|message| format!(" {message}."));
+        // Without parameters the error has no variants, and a `match` without arms is all it needs.
+        let display = if variants.is_empty() {
+            quote! { match *self {} }
+        } else {
+            quote! {
+                let message = match *self {
+                    #(#error::#variants => #messages,)*
+                };
+                f.write_str(message)
+            }
+        };
+        quote! {
+            #[doc = #builder_doc]
+            #[must_use]
+            #struct_vis struct #builder<#(#decl_params),*> #where_clause {
+                #(#names: ::core::option::Option<#tys>,)*
+                __marker: ::core::marker::PhantomData<fn() -> #target>,
+            }
+
+            #[automatically_derived]
+            impl<#(#impl_params),*> ::core::default::Default for #builder<#(#args),*>
+            #where_clause
+            {
+                fn default() -> Self {
+                    Self {
+                        #(#names: ::core::option::Option::None,)*
+                        __marker: ::core::marker::PhantomData,
+                    }
+                }
+            }
+
+            #[automatically_derived]
+            impl<#(#impl_params),*> #builder<#(#args),*> #where_clause {
+                #(
+                    #[allow(non_snake_case)]
+                    #vis fn #names(mut self, #names: #tys) -> Self {
+                        self.#names = ::core::option::Option::Some(#names);
+                        self
+                    }
+                )*
+
+                /// Builds the value from the parameters set on the builder, or reports the first
+                /// one that was not set.
+                #vis #asyncness fn build(self) -> ::core::result::Result<#output, #error> {
+                    #(
+                        let ::core::option::Option::Some(#names) = self.#names else {
+                            return ::core::result::Result::Err(#error::#variants);
+                        };
+                    )*
+                    ::core::result::Result::Ok(#ctor(#(#call_args),*) #await_ctor)
+                }
+            }
+
+            #[doc = #error_doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #struct_vis enum #error {
+                #(
+                    #[doc = #variant_docs]
+                    #variants,
+                )*
+            }
+
+            #[automatically_derived]
+            impl ::core::fmt::Display for #error {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    #display
+                }
+            }
+
+            #[automatically_derived]
+            impl ::std::error::Error for #error {}
+        }
+    }
+}
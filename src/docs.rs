@@ -0,0 +1,61 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::Ident;
+
+/// Renders tokens as they were written in the source, falling back to their token string.
+pub(crate) fn source_text(tokens: &impl ToTokens) -> String {
+    tokens
+        .span()
+        .source_text()
+        .unwrap_or_else(|| tokens.to_token_stream().to_string())
+}
+
+/// Builds the `# Arguments` doc section listing every parameter, as `(name, type)` pairs.
+pub(crate) fn arguments_section(args: &[(String, String)]) -> TokenStream {
+    if args.is_empty() {
+        return quote! {};
+    }
+
+    let mut lines = vec![String::from(" # Arguments"), String::new()];
+    for (name, ty) in args {
+        lines.push(format!(" - `{name}`: {}", code_span(ty)));
+    }
+    lines.push(String::new());
+
+    quote! { #(#[doc = #lines])* }
+}
+
+/// Builds the `# Defaults` doc section listing every field the constructor fills in by itself.
+pub(crate) fn defaults_section(defaulted: &[(&Ident, String)]) -> TokenStream {
+    if defaulted.is_empty() {
+        return quote! {};
+    }
+
+    let mut lines = vec![
+        String::from(" # Defaults"),
+        String::new(),
+        String::from(" Fields not passed as arguments are initialized as follows:"),
+        String::new(),
+    ];
+    for (ident, source) in defaulted {
+        lines.push(format!(" - `{ident}`: {}", code_span(source)));
+    }
+
+    quote! { #(#[doc = #lines])* }
+}
+
+/// Formats source text as one inline code span. Whitespace, including line breaks, is collapsed
+/// and the span is delimited by more backticks than the text contains, so a multi-line expression
+/// or a string holding a fenced code block cannot open a code block that rustdoc would run as a
+/// doctest.
+pub(crate) fn code_span(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest + 1);
+    if text.starts_with('`') || text.ends_with('`') {
+        format!("{fence} {text} {fence}")
+    } else {
+        format!("{fence}{text}{fence}")
+    }
+}
@@ -0,0 +1,2362 @@
+use crate::attr::{FieldAttrs, StructAttrs};
+use crate::case::Case;
+use crate::checked_builder::CheckedBuilder;
+use crate::typed_builder::TypedBuilder;
+use crate::{case, docs, named_args, ty};
+use proc_macro2::{Delimiter, Ident, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{
+    punctuated::Punctuated, DeriveInput, Expr, ExprAsync, ExprAwait, ExprCall, ExprClosure,
+    ExprMethodCall, ExprTry, Field, FieldsNamed, Generics, LitStr, Macro, Meta, Result, Token,
+    Type, Visibility, WherePredicate,
+};
+
+pub(crate) fn derive(input: DeriveInput) -> Result<TokenStream> {
+    let struct_attrs = StructAttrs::from_attrs(&input.attrs)?;
+    let name = input.ident;
+    let struct_vis = input.vis;
+    let mut generics = input.generics;
+    if !struct_attrs.where_predicates.is_empty() {
+        generics
+            .make_where_clause()
+            .predicates
+            .extend(struct_attrs.where_predicates.iter().cloned());
+    }
+
+    let mut fields = if let syn::Data::Struct(data) = input.data {
+        if let syn::Fields::Named(FieldsNamed { named, .. }) = data.fields {
+            named
+        } else {
+            panic!("`ImplNew` macro can only be used on structs with named fields");
+        }
+    } else {
+        panic!("`ImplNew` macro can only be used on structs");
+    };
+    // `Self` in a field type or a bound stands for the struct, which it no longer does in a free
+    // function, a builder or an impl for a `self_ty`, so it is spelled out.
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let struct_ty = quote! { #name #ty_generics };
+    for param in &mut generics.params {
+        *param = ty::replace_self(param, &struct_ty);
+    }
+    if let Some(where_clause) = &mut generics.where_clause {
+        *where_clause = ty::replace_self(where_clause, &struct_ty);
+    }
+    for field in &mut fields {
+        field.ty = ty::replace_self(&field.ty, &struct_ty);
+    }
+
+    if struct_attrs.default_new {
+        return default_new(&struct_attrs, &name, &generics, &fields);
+    }
+
+    let error = match &struct_attrs.error {
+        Some(error) => quote! { #error },
+        None if struct_attrs.fallible => quote! { ::core::convert::Infallible },
+        None => quote! { ::std::boxed::Box<dyn ::std::error::Error + Send + Sync> },
+    };
+
+    let mut params: Vec<Param> = Vec::new();
+    let mut inits = Vec::new();
+    let mut defaulted = Vec::new();
+    let mut exposed_defaults = Vec::new();
+    let mut eager_defaults = Vec::new();
+    let mut implicit_defaults = Vec::new();
+    let mut setters = Vec::new();
+    let mut param_inits = Vec::new();
+    // Initializers of the constructor's struct literal, `None` for the defaulted fields, which are
+    // bound to locals beforehand.
+    let mut ctor_inits = Vec::new();
+    let mut default_values = Vec::new();
+    let mut map_lets = Vec::new();
+    let mut checks = Vec::new();
+    // `from_map` reports every failure as an error, whatever form `checks` takes.
+    let mut map_checks = Vec::new();
+    // Parameters that `optional_params` turned into an `Option` of the field's type.
+    let mut optional_names = Vec::new();
+    let mut unboxed = false;
+    let mut track_caller = false;
+    let mut timed = Vec::new();
+    // What keeps the constructor from being a `const fn`, and whether some initializer calls a
+    // function that may or may not be const.
+    let mut const_blockers = Vec::new();
+    let mut maybe_non_const = false;
+    let mut map_inits = Vec::new();
+    // Resolved at the macro definition, so a field named `map` cannot shadow the argument.
+    let map = Ident::new("map", Span::mixed_site());
+
+    let field_attrs = fields
+        .iter()
+        .map(|field| FieldAttrs::from_field(field, struct_attrs.ignore_unknown))
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(listed) = &struct_attrs.params {
+        for ident in listed {
+            if !fields
+                .iter()
+                .any(|field| field.ident.as_ref() == Some(ident))
+            {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    format!("`params` lists `{ident}`, but `{name}` has no such field"),
+                ));
+            }
+        }
+    }
+
+    // Path the hoisted `eager_default` constants are reached through from the constructor body.
+    let const_owner = match (&struct_attrs.free_fn, &struct_attrs.self_ty) {
+        (None, _) => quote! { Self },
+        (Some(_), Some(self_ty)) => quote! { <#self_ty> },
+        (Some(_), None) => {
+            let (_, ty_generics, _) = generics.split_for_impl();
+            let turbofish = ty_generics.as_turbofish();
+            quote! { #name #turbofish }
+        }
+    };
+
+    for (field, field_attrs) in fields.iter().zip(&field_attrs) {
+        let ident = &field.ident;
+
+        let phantom_pinned = ty::is_phantom_pinned(&field.ty);
+        let phantom = phantom_pinned || ty::is_phantom_data(&field.ty);
+        // Whether the field is a parameter unless it has a default: the fields listed in
+        // `params(...)` if given, the public fields otherwise.
+        let public = match &struct_attrs.params {
+            Some(listed) => ident.as_ref().is_some_and(|ident| listed.contains(ident)),
+            None => matches!(field.vis, Visibility::Public(_)),
+        };
+
+        if let Some(members) = &field_attrs.flatten {
+            if struct_attrs.from_map {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "`from_map` cannot parse `flatten` fields",
+                ));
+            }
+            if let Some(group) = &field_attrs.group {
+                return Err(syn::Error::new_spanned(
+                    group,
+                    "`flatten` fields cannot be grouped",
+                ));
+            }
+            let names = members
+                .iter()
+                .map(|(name, _)| param_name(name, struct_attrs.rename_all))
+                .collect::<Vec<_>>();
+            let ty = &field.ty;
+            maybe_non_const = true;
+            inits.push(quote! { #ident: <#ty>::new(#(#names),*) });
+            param_inits.extend(inits.last().cloned());
+            ctor_inits.push(inits.last().cloned());
+            for ((key, ty), name) in members.iter().zip(names) {
+                params.push(Param::Single(Arg {
+                    field,
+                    name,
+                    key: Some(key.clone()),
+                    attrs: field_attrs.param_attrs.clone(),
+                    ty: quote! { #ty },
+                }));
+            }
+            continue;
+        }
+
+        if field_attrs.init.is_none()
+            && (!(public || field_attrs.from_iter)
+                || field_attrs.default.is_some()
+                || field_attrs.skip_if_default
+                || field_attrs.type_name
+                || field_attrs.caller_location
+                || field_attrs.timed
+                || phantom)
+        {
+            if let Some(group) = &field_attrs.group {
+                return Err(syn::Error::new_spanned(
+                    group,
+                    "only constructor parameters can be grouped",
+                ));
+            }
+            if let Some(conversion) = field_attrs
+                .conversions()
+                .first()
+                .or(field_attrs.non_empty.then_some(&"non_empty"))
+                .or(field_attrs.regex.is_some().then_some(&"regex"))
+            {
+                // `optional_params` makes such a public field an `Option` parameter all the same.
+                let message = if struct_attrs.optional_params && public && !phantom {
+                    format!("`{conversion}` cannot be combined with `optional_params`")
+                } else {
+                    format!("`{conversion}` only applies to constructor parameters")
+                };
+                return Err(syn::Error::new_spanned(field, message));
+            }
+            let (value, source) = match &field_attrs.default {
+                None if field_attrs.caller_location => {
+                    if struct_attrs.is_async || struct_attrs.extern_c {
+                        return Err(syn::Error::new_spanned(
+                            field,
+                            "`caller_location` needs `#[track_caller]`, which `async` and \
+                             `extern_c` constructors cannot have",
+                        ));
+                    }
+                    track_caller = true;
+                    maybe_non_const = true;
+                    (
+                        quote! { ::core::panic::Location::caller() },
+                        String::from("Location::caller()"),
+                    )
+                }
+                None if field_attrs.timed => {
+                    if !ty::is_duration(&field.ty) {
+                        return Err(syn::Error::new_spanned(
+                            &field.ty,
+                            "`timed` expects a `Duration` field",
+                        ));
+                    }
+                    const_blockers.push((field.span(), "`timed` reads the clock"));
+                    timed.push(ident.as_ref().expect("named field"));
+                    (
+                        quote! { ::core::time::Duration::ZERO },
+                        String::from("Instant::elapsed()"),
+                    )
+                }
+                None if field_attrs.type_name => {
+                    if !ty::is_string_like(&field.ty) {
+                        return Err(syn::Error::new_spanned(
+                            &field.ty,
+                            "`type_name` expects a string field such as `&'static str` or `String`",
+                        ));
+                    }
+                    let type_name = name.unraw().to_string();
+                    const_blockers
+                        .push((field.span(), "`type_name` converts the name with `Into`"));
+                    (
+                        quote! { ::core::convert::Into::into(#type_name) },
+                        format!("{type_name:?}"),
+                    )
+                }
+                Some(expr) if !struct_attrs.try_new && contains_try(expr) => {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        "`?` in a default expression requires `#[new(try_new)]`",
+                    ));
+                }
+                Some(expr) if !struct_attrs.is_async && contains_await(expr) => {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        "`.await` in a default expression requires `#[new(async)]`",
+                    ));
+                }
+                Some(expr) if struct_attrs.eager_default && is_literal(expr) => {
+                    let const_name = format_ident!(
+                        "__DEFAULT_{}",
+                        case::to_screaming_snake_case(
+                            &ident.as_ref().expect("named field").unraw().to_string()
+                        )
+                    );
+                    let ty = &field.ty;
+                    eager_defaults.push(quote! {
+                        #[doc(hidden)]
+                        const #const_name: #ty = #expr;
+                    });
+                    (
+                        quote! { #const_owner::#const_name },
+                        docs::source_text(expr),
+                    )
+                }
+                Some(expr) => {
+                    if contains_try(expr) {
+                        const_blockers.push((expr.span(), "`?` is not allowed in a `const fn`"));
+                    }
+                    maybe_non_const |= contains_call(expr);
+                    (quote! { #expr }, docs::source_text(expr))
+                }
+                None if phantom_pinned => (
+                    quote! { ::core::marker::PhantomPinned },
+                    String::from("PhantomPinned"),
+                ),
+                None if phantom => (
+                    quote! { ::core::marker::PhantomData },
+                    String::from("PhantomData"),
+                ),
+                None if ty::is_once_cell(&field.ty) => {
+                    let ty = &field.ty;
+                    (
+                        quote! { <#ty>::new() },
+                        format!("<{}>::new()", docs::source_text(ty)),
+                    )
+                }
+                None if struct_attrs.defaults_from.is_some() && ident.is_some() => {
+                    let defaults = struct_attrs.defaults_from.as_ref().expect("checked above");
+                    let ident = ident.as_ref().expect("checked above");
+                    (
+                        quote! { #defaults.#ident },
+                        format!("{}.{}", docs::source_text(defaults), ident.unraw()),
+                    )
+                }
+                None if struct_attrs.zeroed => {
+                    implicit_defaults.push(field);
+                    const_blockers.push((field.span(), "`Zeroable::zeroed()` is not const"));
+                    let ty = &field.ty;
+                    (
+                        quote_spanned! {ty.span()=> <#ty as ::bytemuck::Zeroable>::zeroed() },
+                        String::from("Zeroable::zeroed()"),
+                    )
+                }
+                None => {
+                    implicit_defaults.push(field);
+                    const_blockers.push((
+                        field.span(),
+                        "`Default::default()` is not const, so the field needs a `#[new(default(...))]`",
+                    ));
+                    // Spanned at the field type so a missing `Default` impl is reported there.
+                    (
+                        quote_spanned! {field.ty.span()=> Default::default() },
+                        String::from("Default::default()"),
+                    )
+                }
+            };
+            if struct_attrs.expose_defaults {
+                if let (Some(ident), Some(expr)) = (ident, &field_attrs.default) {
+                    if is_literal(expr) {
+                        exposed_defaults.push((ident, &field.ty, expr));
+                    }
+                }
+            }
+            let (value, optional) = match ident {
+                Some(ident) if struct_attrs.optional_params && public && !phantom => {
+                    let param = field_attrs
+                        .rename
+                        .clone()
+                        .unwrap_or_else(|| param_name(ident, struct_attrs.rename_all));
+                    let given = Ident::new("value", Span::mixed_site());
+                    let some = quote! { ::core::option::Option::Some(#given) => #given };
+                    let none = quote! { ::core::option::Option::None };
+                    param_inits.push(quote! {
+                        #ident: match #param { #some, #none => base.#ident }
+                    });
+                    if struct_attrs.from_map {
+                        let key = ident.unraw().to_string();
+                        let invalid = failure_message(&name, field, "is invalid");
+                        let ty = &field.ty;
+                        map_lets.push(quote! {
+                            let #param: ::core::option::Option<#ty> = match #map.get(#key) {
+                                ::core::option::Option::Some(value) => ::core::option::Option::Some(
+                                    <#ty as ::core::str::FromStr>::from_str(value).map_err(|err| {
+                                        ::std::format!("{}: {}", #invalid, err)
+                                    })?,
+                                ),
+                                #none => #none,
+                            };
+                        });
+                    }
+                    let ty = &field.ty;
+                    optional_names.push(param.clone());
+                    params.push(Param::Single(Arg {
+                        field,
+                        name: param.clone(),
+                        key: None,
+                        attrs: field_attrs.param_attrs.clone(),
+                        ty: quote! { ::core::option::Option<#ty> },
+                    }));
+                    (quote! { match #param { #some, #none => #value } }, true)
+                }
+                _ => (value, false),
+            };
+            if let (true, true, false, false, Some(ident)) = (
+                struct_attrs.builder,
+                matches!(field.vis, Visibility::Public(_)),
+                phantom,
+                optional,
+                ident,
+            ) {
+                let setter = format_ident!("with_{}", ident.unraw());
+                let param = field_attrs
+                    .rename
+                    .clone()
+                    .unwrap_or_else(|| param_name(ident, struct_attrs.rename_all));
+                let ty = &field.ty;
+                let (param_ty, value) = if struct_attrs.builder_into {
+                    (
+                        quote! { impl ::core::convert::Into<#ty> },
+                        quote! { ::core::convert::Into::into(#param) },
+                    )
+                } else {
+                    (quote! { #ty }, quote! { #param })
+                };
+                setters.push(quote! {
+                    #[must_use]
+                    pub fn #setter(mut self, #param: #param_ty) -> Self {
+                        self.#ident = #value;
+                        self
+                    }
+                });
+            }
+            inits.push(quote! { #ident: #value });
+            ctor_inits.push(None);
+            map_inits.push(None);
+            default_values.push((ident.as_ref().expect("named field"), &field.ty, value));
+            defaulted.extend(ident.as_ref().map(|i| (i, source)));
+            continue;
+        }
+
+        let param = match &field_attrs.init {
+            Some(Some(name)) => name.clone(),
+            Some(None) => {
+                let base = format!(
+                    "{}_init",
+                    field.ident.as_ref().expect("named field").unraw()
+                );
+                param_name(&Ident::new(&base, field.span()), struct_attrs.rename_all)
+            }
+            None => field_attrs.rename.clone().unwrap_or_else(|| {
+                param_name(
+                    field.ident.as_ref().expect("named field"),
+                    struct_attrs.rename_all,
+                )
+            }),
+        };
+        let arg = if field_attrs.try_param {
+            if !struct_attrs.try_new {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "`try` requires `#[new(try_new)]`",
+                ));
+            }
+            inits.push(quote! {
+                #ident: ::core::result::Result::map_err(
+                    #param,
+                    ::core::convert::Into::<#error>::into,
+                )?
+            });
+            let ty = &field.ty;
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: quote! { ::core::result::Result<#ty, impl ::core::convert::Into<#error>> },
+            }
+        } else if field_attrs.from_iter {
+            inits.push(quote! {
+                #ident: ::core::iter::Iterator::collect(::core::iter::IntoIterator::into_iter(#param))
+            });
+            let item = ty::iter_item(&field.ty);
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: quote! { impl ::core::iter::IntoIterator<Item = #item> },
+            }
+        } else if field_attrs.nonzero {
+            let ty = &field.ty;
+            let Some(raw) = ty::nonzero_inner(ty) else {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "`nonzero` expects a `NonZero*` field such as `NonZeroU32`",
+                ));
+            };
+            if struct_attrs.try_new {
+                inits.push(quote! {
+                    #ident: ::core::result::Result::map_err(
+                        <#ty as ::core::convert::TryFrom<#raw>>::try_from(#param),
+                        ::core::convert::Into::<#error>::into,
+                    )?
+                });
+            } else {
+                let message = failure_message(&name, field, "must be non-zero");
+                inits.push(quote! {
+                    #ident: ::core::option::Option::expect(<#ty>::new(#param), #message)
+                });
+            }
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: quote! { #raw },
+            }
+        } else if let Some(unit) = field_attrs.duration {
+            let ty = &field.ty;
+            if !ty::is_duration(ty) {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    format!("`{unit}` expects a `Duration` field"),
+                ));
+            }
+            let unit = Ident::new(unit, field.span());
+            inits.push(quote! { #ident: <#ty>::#unit(#param) });
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: quote! { u64 },
+            }
+        } else if field_attrs.init.is_some() {
+            inits.push(quote! { #ident: #param() });
+            let ty = &field.ty;
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: quote! { impl ::core::ops::FnOnce() -> #ty },
+            }
+        } else if field_attrs.lock || field_attrs.cell {
+            let ty = &field.ty;
+            let (wrappers, message) = if field_attrs.lock {
+                (
+                    ["Mutex", "RwLock"],
+                    "`lock` expects a `Mutex<T>` or `RwLock<T>` field",
+                )
+            } else {
+                (
+                    ["Cell", "RefCell"],
+                    "`cell` expects a `Cell<T>` or `RefCell<T>` field",
+                )
+            };
+            let Some(inner) = ty::wrapped_inner(ty, &wrappers) else {
+                return Err(syn::Error::new_spanned(ty, message));
+            };
+            inits.push(quote! { #ident: <#ty>::new(#param) });
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: quote! { #inner },
+            }
+        } else if field_attrs.param_ty.is_some() || field_attrs.convert.is_some() {
+            let ty = &field.ty;
+            let value = match &field_attrs.convert {
+                Some(expr) if !struct_attrs.try_new && contains_try(expr) => {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        "`?` in a `convert` expression requires `#[new(try_new)]`",
+                    ));
+                }
+                Some(expr) => {
+                    if contains_try(expr) {
+                        const_blockers.push((expr.span(), "`?` is not allowed in a `const fn`"));
+                    }
+                    maybe_non_const |= contains_call(expr);
+                    quote! { #expr }
+                }
+                None => {
+                    const_blockers.push((field.span(), "`param_ty` converts with `Into`"));
+                    quote! { ::core::convert::Into::into(#param) }
+                }
+            };
+            inits.push(quote! { #ident: #value });
+            let param_ty = match &field_attrs.param_ty {
+                Some(param_ty) => quote! { #param_ty },
+                None => quote! { #ty },
+            };
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: param_ty,
+            }
+        } else if let Some(range) = &field_attrs.clamp {
+            let value = match (&range.start, &range.end) {
+                (Some(start), Some(end)) => quote! { #param.clamp(#start, #end) },
+                (Some(start), None) => quote! { #param.max(#start) },
+                (None, end) => quote! { #param.min(#end) },
+            };
+            inits.push(quote! { #ident: #value });
+            let ty = &field.ty;
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: quote! { #ty },
+            }
+        } else if let Some(interner) = &field_attrs.intern {
+            if !struct_attrs
+                .resources
+                .iter()
+                .any(|(name, _)| name == interner)
+            {
+                return Err(syn::Error::new_spanned(
+                    interner,
+                    format!(
+                        "`intern` names `{interner}`, which is not a resource of the struct; \
+                         declare it with `#[new(resource({interner}: &mut Interner))]`"
+                    ),
+                ));
+            }
+            inits.push(quote! { #ident: #interner.intern(#param) });
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: quote! { &str },
+            }
+        } else if let Some(target) = &field_attrs.as_ref {
+            inits.push(quote! {
+                #ident: ::std::borrow::ToOwned::to_owned(
+                    ::core::convert::AsRef::<#target>::as_ref(&#param),
+                )
+            });
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: quote! { impl ::core::convert::AsRef<#target> },
+            }
+        } else if field_attrs.unbox {
+            let ty = &field.ty;
+            if ty::unsized_hint(ty).is_some() {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "`unbox` cannot move an unsized value out of its box; store the `Box` instead",
+                ));
+            }
+            unboxed = true;
+            inits.push(quote! { #ident: *#param });
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: quote! { ::std::boxed::Box<#ty> },
+            }
+        } else if struct_attrs.str_params && !field_attrs.no_str && ty::is_string(&field.ty) {
+            inits.push(quote! { #ident: <str as ::std::borrow::ToOwned>::to_owned(#param) });
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: quote! { &str },
+            }
+        } else {
+            if let Some(hint) = ty::unsized_hint(&field.ty) {
+                return Err(syn::Error::new_spanned(&field.ty, hint));
+            }
+            inits.push(match ident {
+                Some(ident) if *ident == param => quote! { #ident },
+                _ => quote! { #ident: #param },
+            });
+            let ty = &field.ty;
+            Arg {
+                field,
+                name: param,
+                key: None,
+                attrs: field_attrs.param_attrs.clone(),
+                ty: quote! { #ty },
+            }
+        };
+
+        // A builder keeps each parameter in a field, where an `impl Trait` type is not allowed.
+        let builder_option = match (struct_attrs.typed_builder, struct_attrs.checked_builder) {
+            (true, _) => Some("typed_builder"),
+            (_, true) => Some("checked_builder"),
+            _ => None,
+        };
+        if let Some(option) = builder_option {
+            if syn::parse2::<Type>(arg.ty.clone()).is_ok_and(|ty| matches!(ty, Type::ImplTrait(_)))
+            {
+                let message = match field_attrs.conversions().first() {
+                    Some(conversion) => format!(
+                        "`{option}` cannot store `{conversion}` parameters, \
+                         whose type is an `impl Trait`"
+                    ),
+                    None => {
+                        format!("`{option}` cannot store a parameter whose type is an `impl Trait`")
+                    }
+                };
+                return Err(syn::Error::new_spanned(field, message));
+            }
+        }
+
+        param_inits.extend(inits.last().cloned());
+        ctor_inits.push(inits.last().cloned());
+
+        let converted = field_attrs.conversions().into_iter().find(|conversion| {
+            [
+                "from_iter",
+                "try",
+                "init",
+                "unbox",
+                "clamp",
+                "intern",
+                "as_ref",
+            ]
+            .contains(conversion)
+                || (*conversion == "nonzero" && struct_attrs.try_new)
+        });
+        if let Some(conversion) = converted {
+            const_blockers.push((field.span(), conversion_blocker(conversion)));
+        } else if field_attrs.conversions().is_empty()
+            && struct_attrs.str_params
+            && !field_attrs.no_str
+            && ty::is_string(&field.ty)
+        {
+            const_blockers.push((
+                field.span(),
+                "`str_params` copies the `&str` into a `String`",
+            ));
+        }
+        if field_attrs.non_empty {
+            const_blockers.push((field.span(), "`non_empty` checks are not const"));
+            if let Some(conversion) = field_attrs.conversions().first() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("`non_empty` cannot be combined with `{conversion}`"),
+                ));
+            }
+            let param = &arg.name;
+            let message = failure_message(&name, field, "must not be empty");
+            let fail = quote! {
+                if #param.is_empty() {
+                    return ::core::result::Result::Err(::core::convert::From::from(#message));
+                }
+            };
+            checks.push(if struct_attrs.try_new {
+                fail.clone()
+            } else {
+                quote! {
+                    ::core::assert!(!#param.is_empty(), "{}", #message);
+                }
+            });
+            map_checks.push(fail);
+        }
+
+        if let Some(pattern) = &field_attrs.regex {
+            const_blockers.push((pattern.span(), "`regex` checks are not const"));
+            if let Some(conversion) = field_attrs.conversions().first() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("`regex` cannot be combined with `{conversion}`"),
+                ));
+            }
+            let param = &arg.name;
+            let message = failure_message(
+                &name,
+                field,
+                &format!("does not match `{}`", pattern.value()),
+            );
+            let invalid = format!("invalid `regex` pattern `{}`", pattern.value());
+            // Compiled once, on the first call, and shared by every later one.
+            let compiled = Ident::new("PATTERN", Span::mixed_site());
+            let matches = quote! {
+                {
+                    static #compiled: ::std::sync::LazyLock<::regex::Regex> =
+                        ::std::sync::LazyLock::new(|| {
+                            ::core::result::Result::expect(::regex::Regex::new(#pattern), #invalid)
+                        });
+                    #compiled.is_match(::core::convert::AsRef::<str>::as_ref(&#param))
+                }
+            };
+            let fail = quote! {
+                if !#matches {
+                    return ::core::result::Result::Err(::core::convert::From::from(#message));
+                }
+            };
+            checks.push(if struct_attrs.try_new {
+                fail.clone()
+            } else {
+                quote! {
+                    ::core::assert!(#matches, "{}", #message);
+                }
+            });
+            map_checks.push(fail);
+        }
+
+        if struct_attrs.from_map {
+            if let Some(conversion) = field_attrs.conversions().first() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("`from_map` cannot parse `{conversion}` fields"),
+                ));
+            }
+            let key = field
+                .ident
+                .as_ref()
+                .expect("named field")
+                .unraw()
+                .to_string();
+            let missing = failure_message(&name, field, "is missing from the map");
+            let invalid = failure_message(&name, field, "is invalid");
+            let ty = &field.ty;
+            let param = &arg.name;
+            map_inits.push(Some(quote! { #ident: #param }));
+            map_lets.push(quote! {
+                let #param: #ty = match #map.get(#key) {
+                    ::core::option::Option::Some(value) => {
+                        <#ty as ::core::str::FromStr>::from_str(value).map_err(|err| {
+                            ::std::format!("{}: {}", #invalid, err)
+                        })?
+                    }
+                    ::core::option::Option::None => {
+                        return ::core::result::Result::Err(::core::convert::From::from(#missing));
+                    }
+                };
+            });
+        }
+
+        match &field_attrs.group {
+            Some(group) => {
+                let existing = params.iter_mut().find_map(|param| match param {
+                    Param::Group { name, members } if name.value() == group.value() => {
+                        Some(members)
+                    }
+                    _ => None,
+                });
+                match existing {
+                    Some(members) => members.push(arg),
+                    None => params.push(Param::Group {
+                        name: group.clone(),
+                        members: vec![arg],
+                    }),
+                }
+            }
+            None => params.push(Param::Single(arg)),
+        }
+    }
+
+    // Defaults are computed before the struct literal moves any parameter, so they can borrow the
+    // parameters and the earlier defaulted fields by name. They are bound to hygienic locals, and
+    // only a field a later default mentions is also visible under its own name, inside a block of
+    // its own, so it shadows nothing in `convert`, `post` or other code after the defaults. A
+    // field whose name is taken by a parameter, e.g. a `flatten` member or a `resource`, is never
+    // visible. A default that neither calls anything nor mentions a parameter or another field,
+    // such as `[0; 4096]`, is written into the struct literal directly, without a local to move
+    // out of.
+    let param_names = params
+        .iter()
+        .flat_map(Param::names)
+        .chain(struct_attrs.resources.iter().map(|(name, _)| name))
+        .cloned()
+        .collect::<Vec<_>>();
+    let scope_names = param_names
+        .iter()
+        .chain(default_values.iter().map(|(ident, _, _)| *ident))
+        .collect::<Vec<_>>();
+    let mut bound = Vec::new();
+    let mut default_fields = Vec::new();
+    for (i, (ident, ty, value)) in default_values.iter().enumerate() {
+        let referenced = default_values[i + 1..]
+            .iter()
+            .any(|(_, _, later)| mentions(later, ident));
+        let inline = !referenced
+            && syn::parse2::<Expr>(value.clone()).is_ok_and(|expr| !contains_call(&expr))
+            && !scope_names.iter().any(|name| mentions(value, name));
+        if inline {
+            default_fields.push(quote! { #ident: #value });
+            continue;
+        }
+        let hidden = Ident::new(&format!("default_{}", ident.unraw()), Span::mixed_site());
+        let visible = (referenced && !param_names.contains(ident)).then_some(*ident);
+        default_fields.push(quote! { #ident: #hidden });
+        bound.push((hidden, visible, *ty, value));
+    }
+    let default_lets = if bound.iter().any(|(_, visible, _, _)| visible.is_some()) {
+        let hidden = bound.iter().map(|(hidden, _, _, _)| hidden);
+        let inner = bound
+            .iter()
+            .map(|(hidden, visible, _, _)| visible.unwrap_or(hidden))
+            .collect::<Vec<_>>();
+        let tys = bound.iter().map(|(_, _, ty, _)| ty);
+        let values = bound.iter().map(|(_, _, _, value)| value);
+        vec![quote! {
+            let (#(#hidden,)*) = {
+                #(let #inner: #tys = #values;)*
+                (#(#inner,)*)
+            };
+        }]
+    } else {
+        bound
+            .iter()
+            .map(|(hidden, _, ty, value)| quote! { let #hidden: #ty = #value; })
+            .collect()
+    };
+    let fill = |inits: Vec<Option<TokenStream>>| {
+        let mut defaults = default_fields.iter().cloned();
+        inits
+            .into_iter()
+            .map(|init| init.unwrap_or_else(|| defaults.next().expect("defaulted field")))
+            .collect::<Vec<_>>()
+    };
+    let ctor_inits = fill(ctor_inits);
+    let map_inits = fill(map_inits);
+
+    if struct_attrs.sorted_params {
+        params.sort_by_cached_key(|param| {
+            param
+                .fields()
+                .first()
+                .and_then(|field| field.ident.as_ref())
+                .map(|ident| ident.unraw().to_string())
+        });
+    }
+
+    if struct_attrs.field_order_check {
+        // `sorted_params` and groups, which take the place of their first member, both move fields.
+        let position = |field: &Field| {
+            fields
+                .iter()
+                .position(|declared| std::ptr::eq(declared, field))
+        };
+        let ordered = params.iter().flat_map(Param::fields).collect::<Vec<_>>();
+        if let Some(pair) = ordered
+            .windows(2)
+            .find(|pair| position(pair[0]) > position(pair[1]))
+        {
+            let name = |field: &Field| field.ident.as_ref().map(|ident| ident.unraw().to_string());
+            return Err(syn::Error::new_spanned(
+                pair[1],
+                format!(
+                    "`field_order_check`: the parameter for `{}` comes after the one for `{}`, \
+                     which is declared later",
+                    name(pair[1]).unwrap_or_default(),
+                    name(pair[0]).unwrap_or_default(),
+                ),
+            ));
+        }
+    }
+
+    if struct_attrs.as_tuple_args && !params.is_empty() {
+        let mut members = Vec::new();
+        for param in params {
+            match param {
+                Param::Single(arg) => members.push(arg),
+                Param::Group { name, .. } => {
+                    return Err(syn::Error::new_spanned(
+                        name,
+                        "`group` cannot be combined with `as_tuple_args`, \
+                         which already passes every parameter as one tuple",
+                    ))
+                }
+            }
+        }
+        params = vec![Param::Group {
+            name: LitStr::new("as_tuple_args", Span::call_site()),
+            members,
+        }];
+    }
+
+    // A private field of a bare generic type `T` defaults through `T::default()`; add the bound so
+    // a missing `Default` is reported at the call site instead of deep inside the expansion.
+    let default_trait = if struct_attrs.zeroed {
+        quote! { ::bytemuck::Zeroable }
+    } else {
+        quote! { ::core::default::Default }
+    };
+    // Fields marked `skip_default_bound` rely on bounds the caller wrote, such as a trait with
+    // `Default` as a supertrait.
+    let unbounded = fields
+        .iter()
+        .zip(&field_attrs)
+        .filter(|(_, attrs)| attrs.skip_default_bound)
+        .map(|(field, _)| field)
+        .collect::<Vec<_>>();
+    if let Some(field) = unbounded.iter().find(|field| {
+        !implicit_defaults
+            .iter()
+            .any(|default| std::ptr::eq(*default, **field))
+    }) {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`skip_default_bound` only applies to fields initialized with `Default::default()`",
+        ));
+    }
+    // Each bound is added once, and not at all if the struct's where-clause already has it, so
+    // several fields of type `T` or an explicit `where T: Default` do not repeat it.
+    let mut seen = generics
+        .where_clause
+        .iter()
+        .flat_map(|clause| &clause.predicates)
+        .map(|predicate| quote! { #predicate }.to_string())
+        .collect::<Vec<_>>();
+    let default_bounds = implicit_defaults
+        .iter()
+        .filter(|field| ty::is_type_param(&field.ty, &generics))
+        .filter(|field| {
+            !unbounded
+                .iter()
+                .any(|skipped| std::ptr::eq(*skipped, **field))
+        })
+        .map(|field| {
+            let ty = &field.ty;
+            syn::parse_quote! { #ty: #default_trait }
+        })
+        .filter(|predicate: &WherePredicate| {
+            let key = quote! { #predicate }.to_string();
+            let new = !seen.contains(&key);
+            seen.push(key);
+            new
+        })
+        .collect::<Vec<WherePredicate>>();
+    if !default_bounds.is_empty() {
+        generics
+            .make_where_clause()
+            .predicates
+            .extend(default_bounds);
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let fn_name = match &struct_attrs.free_fn {
+        Some(free_fn) => free_fn.clone(),
+        None if struct_attrs.try_new && !struct_attrs.fallible => {
+            Ident::new("try_new", Span::call_site())
+        }
+        None => Ident::new("new", Span::call_site()),
+    };
+
+    let trace = struct_attrs.trace.as_ref().map(|krate| {
+        let args = params.iter().flat_map(Param::names).collect::<Vec<_>>();
+        let call = match &struct_attrs.free_fn {
+            Some(_) => fn_name.to_string(),
+            None => format!("{name}::{fn_name}"),
+        };
+        let format = format!(
+            "{}({})",
+            call,
+            args.iter()
+                .map(|arg| format!("{arg}: {{:?}}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        quote! { ::#krate::trace!(#format, #(#args),*); }
+    });
+
+    let target = match &struct_attrs.self_ty {
+        Some(self_ty) => quote! { #self_ty },
+        None => quote! { #name #ty_generics },
+    };
+
+    let const_default = if struct_attrs.const_default {
+        if let Some(param) = params.first() {
+            return Err(syn::Error::new_spanned(
+                param.fields()[0],
+                "`const_default` requires a default for every field; \
+                 this field is a constructor parameter",
+            ));
+        }
+        if let Some(field) = implicit_defaults.first() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`const_default` requires an explicit `#[new(default(...))]` for every field, \
+                 since `Default::default()` cannot be called in a const",
+            ));
+        }
+        Some(quote! {
+            /// A value with every field set to its default.
+            pub const DEFAULT: Self = {
+                #(#default_lets)*
+                Self {
+                    #(#ctor_inits),*
+                }
+            };
+        })
+    } else {
+        None
+    };
+
+    let named_args = struct_attrs.named_args_macro.then(|| {
+        let ctor = match (&struct_attrs.free_fn, &struct_attrs.self_ty) {
+            (Some(_), _) => quote! { #fn_name },
+            (None, Some(self_ty)) => quote! { <#self_ty>::#fn_name },
+            (None, None) => quote! { #name::#fn_name },
+        };
+        let macro_base = match &struct_attrs.self_ty {
+            Some(syn::Type::Path(path)) => path.path.segments.last().map(|s| &s.ident),
+            _ => None,
+        };
+        named_args::named_args_macro(macro_base.unwrap_or(&name), &ctor, &params)
+    });
+
+    let resources = &struct_attrs.resources;
+
+    let default_impl = if struct_attrs.impl_default {
+        if let Some((resource, _)) = resources.first() {
+            return Err(syn::Error::new_spanned(
+                resource,
+                "`impl_default` requires a constructor without parameters, \
+                 but `resource` adds one",
+            ));
+        }
+        if let Some(param) = params.first() {
+            return Err(syn::Error::new_spanned(
+                param.fields()[0],
+                "`impl_default` requires a constructor without parameters; \
+                 this field is a constructor parameter",
+            ));
+        }
+        if struct_attrs.try_new
+            || struct_attrs.pinned
+            || struct_attrs.wrap.is_some()
+            || struct_attrs.is_async
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`impl_default` requires a constructor returning `Self`, \
+                 which `try_new`, `pinned`, `wrap` and `async` do not",
+            ));
+        }
+        let ctor = match &struct_attrs.free_fn {
+            Some(_) => quote! { #fn_name },
+            None => quote! { Self::#fn_name },
+        };
+        Some(quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::default::Default for #target #where_clause {
+                fn default() -> Self {
+                    #ctor()
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let from_impl = if struct_attrs.impl_from {
+        if let Some((resource, _)) = resources.first() {
+            return Err(syn::Error::new_spanned(
+                resource,
+                "`from` cannot pass the `resource` parameters to the constructor",
+            ));
+        }
+        let [param] = params.as_slice() else {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                format!(
+                    "`from` requires a constructor with exactly one parameter, but it has {}",
+                    params.len()
+                ),
+            ));
+        };
+        if struct_attrs.try_new
+            || struct_attrs.pinned
+            || struct_attrs.wrap.is_some()
+            || struct_attrs.is_async
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`from` requires a constructor returning `Self`, \
+                 which `try_new`, `pinned`, `wrap` and `async` do not",
+            ));
+        }
+        let tys = param.tys();
+        if tys.iter().any(|ty| is_impl_trait(ty)) {
+            return Err(syn::Error::new_spanned(
+                param.fields()[0],
+                "`from` cannot implement `From` for an `impl Trait` parameter",
+            ));
+        }
+        let source = match param {
+            Param::Single(_) => quote! { #(#tys)* },
+            Param::Group { .. } => quote! { (#(#tys,)*) },
+        };
+        let ctor = match &struct_attrs.free_fn {
+            Some(_) => quote! { #fn_name },
+            None => quote! { Self::#fn_name },
+        };
+        Some(quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::convert::From<#source> for #target #where_clause {
+                fn from(value: #source) -> Self {
+                    #ctor(value)
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let marker_impls = struct_attrs
+        .markers
+        .iter()
+        .map(|marker| {
+            quote! {
+                #[automatically_derived]
+                impl #impl_generics #marker for #target #where_clause {}
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // A second inherent `new` for `Foo<u32>` would overlap the generic one, so a concrete
+    // instantiation gets an alias that reaches the generic constructor with its parameters fixed.
+    let mut aliases = Vec::new();
+    for (alias, ty) in &struct_attrs.also_impl_for {
+        let instantiates = match ty {
+            syn::Type::Path(path) if path.qself.is_none() => path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == name),
+            _ => false,
+        };
+        if !instantiates || generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                ty,
+                format!("`also_impl_for` expects an instantiation of the generic `{name}`"),
+            ));
+        }
+        let doc = format!(
+            " `{}`, whose `{alias}::{fn_name}` constructs it.",
+            docs::source_text(ty)
+        );
+        aliases.push(quote! {
+            #[doc = #doc]
+            #struct_vis type #alias = #ty;
+        });
+    }
+
+    let deref_impls = if struct_attrs.deref {
+        let [field] = fields.iter().collect::<Vec<_>>()[..] else {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                format!(
+                    "`deref` requires a struct with exactly one field, but `{name}` has {}",
+                    fields.len()
+                ),
+            ));
+        };
+        let ident = &field.ident;
+        let ty = &field.ty;
+        Some(quote! {
+            #[automatically_derived]
+            impl #impl_generics ::core::ops::Deref for #target #where_clause {
+                type Target = #ty;
+
+                fn deref(&self) -> &Self::Target {
+                    &self.#ident
+                }
+            }
+
+            #[automatically_derived]
+            impl #impl_generics ::core::ops::DerefMut for #target #where_clause {
+                fn deref_mut(&mut self) -> &mut Self::Target {
+                    &mut self.#ident
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    // A public `new()` without `Default` trips `clippy::new_without_default` in the caller's crate.
+    let allow_without_default = (params.is_empty() && resources.is_empty())
+        .then(|| quote! { #[allow(clippy::new_without_default)] });
+
+    // `#[new(unbox)]` parameters are `Box<T>` by design, which `clippy::boxed_local` flags.
+    let allow_boxed = unboxed.then(|| quote! { #[allow(clippy::boxed_local)] });
+
+    // Wide structs would trip `clippy::too_many_arguments`, which callers cannot allow on code they
+    // did not write. Its default threshold is seven parameters.
+    let allow_many =
+        |count: usize| (count > 7).then(|| quote! { #[allow(clippy::too_many_arguments)] });
+    let allow_many_new = allow_many(resources.len() + params.len());
+    let allow_many_with = allow_many(params.len() + 1);
+
+    let fn_generics = if struct_attrs.generics_on_fn {
+        if struct_attrs.free_fn.is_some()
+            || struct_attrs.self_ty.is_some()
+            || struct_attrs.impl_default
+            || struct_attrs.impl_from
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`generics_on_fn` cannot be combined with `free_fn`, `self_ty`, `impl_default` \
+                 or `from`",
+            ));
+        }
+        Some(FnGenerics::split(&generics, &name, &params)?)
+    } else {
+        None
+    };
+
+    let arguments = if struct_attrs.doc_args {
+        let resources = resources
+            .iter()
+            .map(|(name, ty)| (name.unraw().to_string(), docs::source_text(ty)));
+        let fields = params.iter().map(|param| match param {
+            Param::Single(arg) => (arg.name.unraw().to_string(), arg.ty_text()),
+            Param::Group { members, .. } => {
+                let names = members.iter().map(|arg| arg.name.unraw().to_string());
+                let tys = members.iter().map(Arg::ty_text);
+                (
+                    format!("({})", names.collect::<Vec<_>>().join(", ")),
+                    format!("({})", tys.collect::<Vec<_>>().join(", ")),
+                )
+            }
+        });
+        docs::arguments_section(&resources.chain(fields).collect::<Vec<_>>())
+    } else {
+        quote! {}
+    };
+
+    let params_for_with = &params;
+    let params = resources
+        .iter()
+        .map(|(name, ty)| quote! { #name: #ty })
+        .chain(params.iter().map(Param::to_tokens));
+
+    let vis = if struct_attrs.private {
+        quote! {}
+    } else {
+        quote! { pub }
+    };
+
+    let defaults = if struct_attrs.doc_defaults || struct_attrs.doc_args {
+        docs::defaults_section(&defaulted)
+    } else {
+        quote! {}
+    };
+    let doc = quote! { #arguments #defaults };
+
+    let inline = match &struct_attrs.inline {
+        Some(Some(mode)) => quote! { #[inline(#mode)] },
+        Some(None) => quote! { #[inline] },
+        None => quote! {},
+    };
+
+    let constness = if struct_attrs.is_const {
+        let options = [
+            (struct_attrs.trace.is_some(), "`trace` logs the arguments"),
+            (
+                struct_attrs.post.is_some(),
+                "`post` expressions are not const",
+            ),
+            (struct_attrs.validate_fn.is_some(), "`validate_fn` uses `?`"),
+            (
+                struct_attrs.assert_invariants.is_some(),
+                "`assert_invariants` calls a method that may not be const",
+            ),
+            (struct_attrs.pinned, "`pinned` allocates a `Box`"),
+            (
+                struct_attrs.wrap.is_some(),
+                "`wrap` calls a function that may not be const",
+            ),
+            (
+                struct_attrs.is_async,
+                "`async` constructors cannot be const",
+            ),
+        ];
+        let struct_blockers = options
+            .into_iter()
+            .filter_map(|(set, reason)| set.then_some((Span::call_site(), reason)));
+        let blocker = struct_blockers.chain(const_blockers).next();
+        match blocker {
+            Some(_) if struct_attrs.try_const => None,
+            None if struct_attrs.try_const && maybe_non_const => None,
+            Some((span, reason)) => {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "`const` requires a constructor that can be const, but {reason}; \
+                         add `try_const` to fall back to a non-const constructor"
+                    ),
+                ));
+            }
+            None => Some(quote! { const }),
+        }
+    } else {
+        None
+    };
+
+    let abi = struct_attrs.extern_c.then(|| quote! { extern "C" });
+
+    // `#[new(caller_location)]` records where the constructor was called, not where it is defined.
+    let track_caller = track_caller.then(|| quote! { #[track_caller] });
+
+    let asyncness = struct_attrs.is_async.then(|| quote! { async });
+
+    // Parameters renamed to camelCase or PascalCase would trip the lint in the caller's crate.
+    let allow_case = struct_attrs
+        .rename_all
+        .is_some()
+        .then(|| quote! { #[allow(non_snake_case)] });
+
+    let self_ty = match (&struct_attrs.free_fn, &fn_generics) {
+        (Some(_), _) | (None, Some(_)) => target.clone(),
+        (None, None) => quote! { Self },
+    };
+    let self_path = match (&struct_attrs.free_fn, &fn_generics) {
+        (Some(_), _) | (None, Some(_)) => quote! { #name },
+        (None, None) => quote! { Self },
+    };
+
+    let mut must_use = if struct_attrs.no_must_use {
+        quote! {}
+    } else {
+        quote! { #[must_use] }
+    };
+    let mut output = self_ty.clone();
+    // The constructor's return type spelled without `Self`, for the typed builder's `build`.
+    let mut build_output = target.clone();
+    if let Some(post) = struct_attrs.post.as_ref().filter(|post| contains_try(post)) {
+        if !struct_attrs.try_new {
+            return Err(syn::Error::new_spanned(
+                post,
+                "`?` in a `post` expression requires `#[new(try_new)]`",
+            ));
+        }
+    }
+    // `#[new(timed)]` fields start out zero and are set once the value is complete.
+    let started = Ident::new("started", Span::mixed_site());
+    let start_timer =
+        (!timed.is_empty()).then(|| quote! { let #started = ::std::time::Instant::now(); });
+    // Runs `post`, `validate_fn`, `assert_invariants` and the timers on a freshly built value, for
+    // every function that builds one from parameters.
+    let finish = |mut value: TokenStream| {
+        if let Some(post) = &struct_attrs.post {
+            value = quote! {
+                {
+                    #[allow(unused_mut)]
+                    let mut value = #value;
+                    #post;
+                    value
+                }
+            };
+        }
+        if let Some(validate) = &struct_attrs.validate_fn {
+            value = quote! {
+                {
+                    let value = #value;
+                    #validate(&value)?;
+                    value
+                }
+            };
+        }
+        if let Some(method) = &struct_attrs.assert_invariants {
+            let message = format!("{name}: `{}` does not hold", method.unraw());
+            value = quote! {
+                {
+                    let value = #value;
+                    ::core::debug_assert!(value.#method(), #message);
+                    value
+                }
+            };
+        }
+        if !timed.is_empty() {
+            let value_ident = Ident::new("value", Span::mixed_site());
+            let elapsed = Ident::new("elapsed", Span::mixed_site());
+            value = quote! {
+                {
+                    let mut #value_ident = #value;
+                    let #elapsed = ::std::time::Instant::elapsed(&#started);
+                    #(#value_ident.#timed = #elapsed;)*
+                    #value_ident
+                }
+            };
+        }
+        value
+    };
+    let mut value = finish(quote! {
+        #self_path {
+            #(#ctor_inits),*
+        }
+    });
+    if let (Some(wrap), Some(returns)) = (&struct_attrs.wrap, &struct_attrs.returns) {
+        output = quote! { #returns };
+        value = quote! { #wrap(#value) };
+    }
+    if struct_attrs.pinned {
+        output = quote! { ::core::pin::Pin<::std::boxed::Box<#output>> };
+        build_output = quote! { ::core::pin::Pin<::std::boxed::Box<#build_output>> };
+        value = quote! { ::std::boxed::Box::pin(#value) };
+    }
+    if struct_attrs.try_new {
+        // `Result` is already `#[must_use]`.
+        must_use = quote! {};
+        output = quote! { ::core::result::Result<#output, #error> };
+        build_output = quote! { ::core::result::Result<#build_output, #error> };
+        value = quote! { ::core::result::Result::Ok(#value) };
+    }
+
+    let mut items = Vec::new();
+    let mut free_items = Vec::new();
+
+    match &struct_attrs.free_fn {
+        Some(_) => free_items.push(quote! {
+            #doc
+            #must_use
+            #inline
+            #allow_case
+            #allow_boxed
+            #allow_many_new
+            #track_caller
+            #vis #constness #asyncness #abi fn #fn_name #impl_generics(#(#params),*) -> #output #where_clause {
+                #trace
+                #start_timer
+                #(#checks)*
+                #(#default_lets)*
+                #value
+            }
+        }),
+        None => {
+            let moved = fn_generics.as_ref().map(|split| &split.moved);
+            let ctor = quote! {
+                #doc
+                #must_use
+                #inline
+                #allow_case
+                #allow_without_default
+                #allow_boxed
+                #allow_many_new
+                #track_caller
+                #vis #constness #asyncness #abi fn #fn_name #moved(#(#params),*) -> #output {
+                    #trace
+                    #start_timer
+                    #(#checks)*
+                    #(#default_lets)*
+                    #value
+                }
+            };
+            match &fn_generics {
+                Some(FnGenerics {
+                    impl_generics,
+                    self_ty,
+                    where_clause,
+                    ..
+                }) => free_items.push(quote! {
+                    #[automatically_derived]
+                    impl #impl_generics #self_ty #where_clause {
+                        #ctor
+                    }
+                }),
+                None => items.push(ctor),
+            }
+        }
+    }
+
+    // Each profile calls the constructor with its values for the parameters, then assigns the
+    // values it gives the other fields.
+    for (profile, defaults) in &struct_attrs.profiles {
+        if struct_attrs.try_new
+            || struct_attrs.pinned
+            || struct_attrs.wrap.is_some()
+            || struct_attrs.is_async
+            || !struct_attrs.resources.is_empty()
+        {
+            return Err(syn::Error::new_spanned(
+                profile,
+                "`profile` cannot be combined with `try_new`, `pinned`, `wrap`, `async` or \
+                 `resource`, since a profile returns the value itself and takes no arguments",
+            ));
+        }
+        let param_keys = params_for_with
+            .iter()
+            .flat_map(Param::keys)
+            .collect::<Vec<_>>();
+        for (key, _) in defaults {
+            if !param_keys.contains(&key) && !fields.iter().any(|f| f.ident.as_ref() == Some(key)) {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    format!("profile `{profile}` sets `{key}`, which is not a field"),
+                ));
+            }
+        }
+        let value_of = |key: &Ident| {
+            defaults
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value)
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        profile,
+                        format!("profile `{profile}` has no value for the parameter `{key}`"),
+                    )
+                })
+        };
+        let args = params_for_with
+            .iter()
+            .map(|param| {
+                // An optional parameter the profile leaves out keeps the field's default.
+                if let Param::Single(arg) = param {
+                    if optional_names.contains(&arg.name) {
+                        let given = arg
+                            .key()
+                            .and_then(|key| defaults.iter().find(|(name, _)| name == key));
+                        return Ok(match given {
+                            Some((_, value)) => quote! { ::core::option::Option::Some(#value) },
+                            None => quote! { ::core::option::Option::None },
+                        });
+                    }
+                }
+                let values = param
+                    .keys()
+                    .into_iter()
+                    .map(value_of)
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(match param {
+                    Param::Single(_) => quote! { #(#values)* },
+                    Param::Group { .. } => quote! { (#(#values,)*) },
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let (assigned, assigned_values): (Vec<_>, Vec<_>) = defaults
+            .iter()
+            .filter(|(key, _)| !param_keys.contains(&key))
+            .map(|(key, value)| (key, value))
+            .unzip();
+        let finished_by = [
+            (struct_attrs.post.is_some(), "post"),
+            (
+                struct_attrs.assert_invariants.is_some(),
+                "assert_invariants",
+            ),
+            (!timed.is_empty(), "timed"),
+        ];
+        if let (Some(key), Some((_, option))) = (
+            assigned.first(),
+            finished_by.iter().find(|(enabled, _)| *enabled),
+        ) {
+            return Err(syn::Error::new_spanned(
+                key,
+                format!(
+                    "profile `{profile}` sets `{key}`, which is not a parameter, so it would be \
+                     assigned after `{option}` has run; make it a parameter or drop `{option}`"
+                ),
+            ));
+        }
+        let ctor = match &struct_attrs.free_fn {
+            Some(_) => quote! { #fn_name },
+            None => quote! { <#target>::#fn_name },
+        };
+        let settings = defaults
+            .iter()
+            .map(|(key, value)| {
+                docs::code_span(&format!("{} = {}", key.unraw(), docs::source_text(value)))
+            })
+            .collect::<Vec<_>>();
+        let doc = if settings.is_empty() {
+            format!(" Creates a value from the `{profile}` profile.")
+        } else {
+            format!(
+                " Creates a value from the `{profile}` profile: {}.",
+                settings.join(", ")
+            )
+        };
+        let value_ident = Ident::new("value", Span::mixed_site());
+        items.push(quote! {
+            #[doc = #doc]
+            #must_use
+            #vis fn #profile() -> #target {
+                #[allow(unused_mut)]
+                let mut #value_ident = #ctor(#(#args),*);
+                #(#value_ident.#assigned = #assigned_values;)*
+                #value_ident
+            }
+        });
+    }
+
+    let type_prefix = struct_attrs
+        .prefix
+        .as_ref()
+        .map(Ident::to_string)
+        .unwrap_or_default();
+    let entry_fn = struct_attrs
+        .builder_fn
+        .clone()
+        .unwrap_or_else(|| Ident::new("builder", Span::call_site()));
+    let typed_builder = struct_attrs.typed_builder.then(|| {
+        let ctor = match &struct_attrs.free_fn {
+            Some(_) => quote! { #fn_name },
+            None => quote! { <#target>::#fn_name },
+        };
+        let builder = TypedBuilder {
+            name: &name,
+            prefix: &type_prefix,
+            entry_fn: &entry_fn,
+            struct_vis: &struct_vis,
+            vis: &vis,
+            generics: &generics,
+            target: &target,
+            ctor: &ctor,
+            output: &build_output,
+            must_use: &must_use,
+            is_async: struct_attrs.is_async,
+            params: params_for_with,
+        };
+        items.push(builder.entry());
+        builder.items()
+    });
+
+    let checked_builder = struct_attrs.checked_builder.then(|| {
+        let ctor = match &struct_attrs.free_fn {
+            Some(_) => quote! { #fn_name },
+            None => quote! { <#target>::#fn_name },
+        };
+        let builder = CheckedBuilder {
+            name: &name,
+            prefix: &type_prefix,
+            entry_fn: &entry_fn,
+            struct_vis: &struct_vis,
+            vis: &vis,
+            generics: &generics,
+            target: &target,
+            ctor: &ctor,
+            output: &build_output,
+            is_async: struct_attrs.is_async,
+            params: params_for_with,
+        };
+        items.push(builder.entry());
+        builder.items()
+    });
+
+    items.extend(const_default);
+    items.extend(setters);
+
+    if struct_attrs.with_overrides {
+        let params = params_for_with.iter().map(Param::to_tokens);
+        let mut value = quote! {
+            Self {
+                #(#param_inits,)*
+                ..base
+            }
+        };
+        let mut output = quote! { Self };
+        if struct_attrs.try_new {
+            value = quote! { ::core::result::Result::Ok(#value) };
+            output = quote! { ::core::result::Result<Self, #error> };
+        }
+        items.push(quote! {
+            /// Creates a value from `base`, replacing the fields passed as parameters.
+            #must_use
+            #allow_case
+            #allow_boxed
+            #allow_many_with
+            #vis fn with(base: Self, #(#params),*) -> #output {
+                #(#checks)*
+                #value
+            }
+        });
+    }
+
+    if struct_attrs.from_map {
+        let map_value = finish(quote! {
+            Self {
+                #(#map_inits),*
+            }
+        });
+        items.push(quote! {
+            /// Creates a value by parsing each parameter with `FromStr` from the entry of `map`
+            /// named after its field, failing if an entry is missing or does not parse.
+            #track_caller
+            #vis fn from_map(
+                #map: &::std::collections::HashMap<::std::string::String, ::std::string::String>,
+            ) -> ::core::result::Result<
+                Self,
+                ::std::boxed::Box<dyn ::std::error::Error + Send + Sync>,
+            > {
+                #start_timer
+                #(#map_lets)*
+                #(#map_checks)*
+                #(#default_lets)*
+                ::core::result::Result::Ok(#map_value)
+            }
+        });
+    }
+
+    if struct_attrs.copy_ctor {
+        items.push(quote! {
+            /// Creates a copy of `other`.
+            #[must_use]
+            #vis fn from_ref(other: &Self) -> Self
+            where
+                Self: ::core::marker::Copy,
+            {
+                *other
+            }
+        });
+    }
+
+    if struct_attrs.singleton {
+        if let Some(param) = params_for_with.first() {
+            return Err(syn::Error::new_spanned(
+                param.fields()[0],
+                "`singleton` requires a constructor without parameters; \
+                 this field is a constructor parameter",
+            ));
+        }
+        if !resources.is_empty()
+            || struct_attrs.try_new
+            || struct_attrs.pinned
+            || struct_attrs.wrap.is_some()
+            || struct_attrs.is_async
+        {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`singleton` requires a constructor without parameters returning `Self`, \
+                 which `resource`, `try_new`, `pinned`, `wrap` and `async` do not give",
+            ));
+        }
+        // A `static` cannot be generic, nor name `Self` from inside the method.
+        if !generics.params.is_empty() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`singleton` cannot be used on a generic struct, since a `static` cannot be generic",
+            ));
+        }
+        let ctor = match &struct_attrs.free_fn {
+            Some(_) => quote! { #fn_name },
+            None => quote! { <#target>::#fn_name },
+        };
+        let instance = Ident::new("INSTANCE", Span::mixed_site());
+        items.push(quote! {
+            /// The shared instance, created with the constructor on first access.
+            #vis fn instance() -> &'static Self {
+                static #instance: ::std::sync::LazyLock<#target> = ::std::sync::LazyLock::new(#ctor);
+                &#instance
+            }
+        });
+    }
+
+    if struct_attrs.from_json {
+        items.push(quote! {
+            /// Deserializes a value from a JSON string. Only available with the `serde` feature.
+            #[cfg(feature = "serde")]
+            #vis fn from_json(json: &str) -> ::core::result::Result<Self, ::serde_json::Error>
+            where
+                Self: ::serde::de::DeserializeOwned,
+            {
+                ::serde_json::from_str(json)
+            }
+        });
+    }
+
+    if struct_attrs.test_full {
+        let idents = fields
+            .iter()
+            .filter_map(|f| f.ident.as_ref())
+            .collect::<Vec<_>>();
+        let params = idents
+            .iter()
+            .map(|ident| param_name(ident, struct_attrs.rename_all))
+            .collect::<Vec<_>>();
+        let tys = fields.iter().map(|f| &f.ty);
+        items.push(quote! {
+            /// Creates a value from every field, private ones included. Only available in tests.
+            #[cfg(test)]
+            #[must_use]
+            #[allow(clippy::too_many_arguments)]
+            #allow_case
+            pub fn new_full(#(#params: #tys),*) -> Self {
+                Self {
+                    #(#idents: #params),*
+                }
+            }
+        });
+    }
+
+    if struct_attrs.metadata {
+        let count = fields.len();
+        let names = fields
+            .iter()
+            .filter_map(|f| f.ident.as_ref())
+            .map(|ident| ident.unraw().to_string());
+        items.push(quote! {
+            /// Number of fields declared on the struct.
+            pub const FIELD_COUNT: usize = #count;
+            /// Names of the fields declared on the struct, in declaration order.
+            pub const FIELD_NAMES: &'static [&'static str] = &[#(#names),*];
+        });
+    }
+
+    items.extend(eager_defaults);
+
+    for (ident, ty, expr) in exposed_defaults {
+        let const_name = format_ident!(
+            "{}_DEFAULT",
+            case::to_screaming_snake_case(&ident.unraw().to_string())
+        );
+        let doc = format!(" Default value of the `{}` field.", ident.unraw());
+        items.push(quote! {
+            #[doc = #doc]
+            pub const #const_name: #ty = #expr;
+        });
+    }
+
+    let inherent = (!items.is_empty()).then(|| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #target #where_clause {
+                #(#items)*
+            }
+        }
+    });
+
+    let deprecations = attr_warnings(&struct_attrs, &field_attrs);
+
+    let cfg = cfg_attr(&struct_attrs);
+    let inherent = inherent.map(|item| quote! { #cfg #item });
+    let default_impl = default_impl.map(|item| quote! { #cfg #item });
+    let from_impl = from_impl.map(|item| quote! { #cfg #item });
+    let named_args = named_args.map(|item| quote! { #cfg #item });
+    free_items.extend(typed_builder);
+    free_items.extend(checked_builder);
+    free_items.extend(marker_impls);
+    free_items.extend(deref_impls);
+    free_items.extend(aliases);
+    let free_items = free_items.iter().map(|item| quote! { #cfg #item });
+    let expanded = quote! {
+        #(#deprecations)*
+        #(#free_items)*
+        #inherent
+        #default_impl
+        #from_impl
+        #named_args
+    };
+
+    Ok(expanded)
+}
+
+/// Warnings for the deprecated `#[default(...)]` attribute and for the unknown keys skipped under
+/// `ignore_unknown`.
+fn attr_warnings(struct_attrs: &StructAttrs, field_attrs: &[FieldAttrs]) -> Vec<TokenStream> {
+    field_attrs
+        .iter()
+        .filter_map(|attrs| attrs.legacy_default.as_ref())
+        .map(|attr| {
+            warning(
+                attr.path().span(),
+                "default",
+                "use `#[new(default(...))]` instead of `#[default(...)]`",
+            )
+        })
+        .chain(
+            struct_attrs
+                .ignored
+                .iter()
+                .chain(field_attrs.iter().flat_map(|attrs| &attrs.ignored))
+                .map(|path| {
+                    let note = format!(
+                        "unknown `new` option `{}` is ignored",
+                        docs::source_text(path)
+                    );
+                    warning(path.span(), "ignored", &note)
+                }),
+        )
+        .collect()
+}
+
+/// A warning at `span`, raised by using a deprecated constant `name`, since stable proc macros
+/// cannot emit warnings directly.
+fn warning(span: Span, name: &str, note: &str) -> TokenStream {
+    let name = Ident::new(name, span);
+    quote_spanned! {span=>
+        const _: () = {
+            #[deprecated(note = #note)]
+            #[allow(non_upper_case_globals)]
+            const #name: () = ();
+            #name
+        };
+    }
+}
+
+/// The `#[cfg(...)]` every generated item is gated behind, if any.
+fn cfg_attr(struct_attrs: &StructAttrs) -> Option<TokenStream> {
+    struct_attrs
+        .cfg
+        .as_ref()
+        .map(|predicate| quote! { #[cfg(#predicate)] })
+}
+
+/// Expands `#[new(default)]` on the struct: a `new()` returning `Default::default()`.
+fn default_new(
+    struct_attrs: &StructAttrs,
+    name: &Ident,
+    generics: &Generics,
+    fields: &Punctuated<Field, Token![,]>,
+) -> Result<TokenStream> {
+    if struct_attrs.is_const && !struct_attrs.try_const {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`const` requires a constructor that can be const, but `Default::default()` is not \
+             const; add `try_const` to fall back to a non-const constructor",
+        ));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let target = match &struct_attrs.self_ty {
+        Some(self_ty) => quote! { #self_ty },
+        None => quote! { #name #ty_generics },
+    };
+    let vis = if struct_attrs.private {
+        quote! {}
+    } else {
+        quote! { pub }
+    };
+    let must_use = (!struct_attrs.no_must_use).then(|| quote! { #[must_use] });
+    let inline = match &struct_attrs.inline {
+        Some(Some(mode)) => quote! { #[inline(#mode)] },
+        Some(None) => quote! { #[inline] },
+        None => quote! {},
+    };
+    // A field's own `#[new(default(...))]` takes precedence over the struct's `Default` impl.
+    let mut overrides = Vec::new();
+    let mut field_attrs = Vec::new();
+    for field in fields {
+        let attrs = FieldAttrs::from_field(field, struct_attrs.ignore_unknown)?;
+        if let Some(key) = attrs.keys.iter().find(|key| *key != "default") {
+            return Err(syn::Error::new_spanned(
+                key,
+                format!("`default` on the struct cannot be combined with the field option `{key}`"),
+            ));
+        }
+        if let Some(expr) = &attrs.default {
+            let ident = &field.ident;
+            overrides.push(quote! { #ident: #expr });
+        }
+        field_attrs.push(attrs);
+    }
+    let warnings = attr_warnings(struct_attrs, &field_attrs);
+    let (value, allow_update) = if overrides.is_empty() {
+        (quote! { ::core::default::Default::default() }, None)
+    } else {
+        let value = quote! {
+            Self {
+                #(#overrides,)*
+                ..::core::default::Default::default()
+            }
+        };
+        // Redundant when every field has its own default, which is fine here.
+        (value, Some(quote! { #[allow(clippy::needless_update)] }))
+    };
+    let cfg = cfg_attr(struct_attrs);
+    Ok(quote! {
+        #(#warnings)*
+        #cfg
+        #[automatically_derived]
+        impl #impl_generics #target #where_clause {
+            #must_use
+            #inline
+            #allow_update
+            #vis fn new() -> Self
+            where
+                Self: ::core::default::Default,
+            {
+                #value
+            }
+        }
+    })
+}
+
+/// The impl a `#[new(generics_on_fn)]` constructor lives in, with the type parameters that only
+/// defaulted fields use moved from the impl onto the function.
+struct FnGenerics {
+    impl_generics: TokenStream,
+    /// The struct with `()` in place of the moved parameters; inherent `new` calls resolve to it.
+    self_ty: TokenStream,
+    where_clause: TokenStream,
+    /// Generic parameter list of the constructor itself.
+    moved: TokenStream,
+}
+
+impl FnGenerics {
+    fn split(generics: &Generics, name: &Ident, params: &[Param]) -> Result<Self> {
+        struct Idents(Vec<Ident>);
+
+        impl<'ast> Visit<'ast> for Idents {
+            fn visit_ident(&mut self, ident: &'ast Ident) {
+                self.0.push(ident.clone());
+            }
+        }
+
+        let mut used = Idents(Vec::new());
+        for field in params.iter().flat_map(Param::fields) {
+            used.visit_type(&field.ty);
+        }
+        let mut bounded = Idents(Vec::new());
+        if let Some(where_clause) = &generics.where_clause {
+            bounded.visit_where_clause(where_clause);
+        }
+
+        let moved = generics
+            .type_params()
+            .filter(|param| !used.0.contains(&param.ident))
+            .collect::<Vec<_>>();
+        if moved.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &generics.params,
+                "`generics_on_fn` found no type parameter used only by defaulted fields",
+            ));
+        }
+        if let Some(param) = moved
+            .iter()
+            .find(|param| !param.bounds.is_empty() || bounded.0.contains(&param.ident))
+        {
+            return Err(syn::Error::new_spanned(
+                param,
+                "`generics_on_fn` can only move type parameters without bounds",
+            ));
+        }
+
+        let moved = moved.iter().map(|param| &param.ident).collect::<Vec<_>>();
+        let args = generics.params.iter().map(|param| match param {
+            syn::GenericParam::Lifetime(def) => {
+                let lifetime = &def.lifetime;
+                quote! { #lifetime }
+            }
+            syn::GenericParam::Type(param) if moved.contains(&&param.ident) => quote! { () },
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote! { #ident }
+            }
+            syn::GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote! { #ident }
+            }
+        });
+        let self_ty = quote! { #name<#(#args),*> };
+
+        let mut reduced = generics.clone();
+        reduced.params = reduced
+            .params
+            .into_iter()
+            .filter(|param| {
+                !matches!(param, syn::GenericParam::Type(param) if moved.contains(&&param.ident))
+            })
+            .collect();
+        let (impl_generics, _, where_clause) = reduced.split_for_impl();
+
+        Ok(Self {
+            impl_generics: quote! { #impl_generics },
+            self_ty,
+            where_clause: quote! { #where_clause },
+            moved: quote! { <#(#moved),*> },
+        })
+    }
+}
+
+/// A public field taken as (part of) a constructor parameter.
+pub(crate) struct Arg<'a> {
+    field: &'a Field,
+    /// Name of the parameter, which differs from the field name under `rename_all`.
+    name: Ident,
+    /// Key of the parameter in the `#[new(macro)]` macro when it is not the field name, as for the
+    /// members of a `#[new(flatten(...))]` field.
+    key: Option<Ident>,
+    /// Attributes from `#[new(param_attr = "...")]`, put on the parameter.
+    attrs: Vec<Meta>,
+    /// Type the caller passes, which differs from the field type for converting fields.
+    ty: TokenStream,
+}
+
+impl Arg<'_> {
+    fn key(&self) -> Option<&Ident> {
+        self.key.as_ref().or(self.field.ident.as_ref())
+    }
+
+    /// The parameter type for docs: as written for a field taken as is, as generated otherwise.
+    fn ty_text(&self) -> String {
+        let field_ty = &self.field.ty;
+        if self.ty.to_string() == quote! { #field_ty }.to_string() {
+            docs::source_text(field_ty)
+        } else {
+            self.ty.to_string()
+        }
+    }
+}
+
+/// A single parameter of the generated `new` function.
+pub(crate) enum Param<'a> {
+    /// A public field passed as its own parameter.
+    Single(Arg<'a>),
+    /// Public fields sharing a `#[new(group = "...")]`, passed together as one tuple parameter.
+    Group { name: LitStr, members: Vec<Arg<'a>> },
+}
+
+impl Param<'_> {
+    pub fn fields(&self) -> Vec<&Field> {
+        match self {
+            Param::Single(arg) => vec![arg.field],
+            Param::Group { members, .. } => members.iter().map(|arg| arg.field).collect(),
+        }
+    }
+
+    /// Keys of the parameters in the `#[new(macro)]` macro, in parameter order.
+    pub fn keys(&self) -> Vec<&Ident> {
+        match self {
+            Param::Single(arg) => arg.key().into_iter().collect(),
+            Param::Group { members, .. } => members.iter().filter_map(Arg::key).collect(),
+        }
+    }
+
+    /// Types the caller passes for the parameters, in field order.
+    pub fn tys(&self) -> Vec<&TokenStream> {
+        match self {
+            Param::Single(arg) => vec![&arg.ty],
+            Param::Group { members, .. } => members.iter().map(|arg| &arg.ty).collect(),
+        }
+    }
+
+    /// Names of the parameters the fields are passed through, in field order.
+    pub fn names(&self) -> Vec<&Ident> {
+        match self {
+            Param::Single(arg) => vec![&arg.name],
+            Param::Group { members, .. } => members.iter().map(|arg| &arg.name).collect(),
+        }
+    }
+
+    fn to_tokens(&self) -> TokenStream {
+        match self {
+            Param::Single(arg) => {
+                let ident = &arg.name;
+                let ty = &arg.ty;
+                let attrs = &arg.attrs;
+                quote! { #(#[#attrs])* #ident: #ty }
+            }
+            Param::Group { members, .. } => {
+                let idents = members.iter().map(|arg| &arg.name);
+                let tys = members.iter().map(|arg| &arg.ty);
+                // Attributes cannot go on the elements of a tuple pattern, so they cover the tuple.
+                let attrs = members.iter().flat_map(|arg| &arg.attrs);
+                quote! { #(#[#attrs])* (#(#idents,)*): (#(#tys,)*) }
+            }
+        }
+    }
+}
+
+/// Returns `true` if the parameter type is an `impl Trait`, which cannot be named in a trait impl.
+fn is_impl_trait(ty: &TokenStream) -> bool {
+    matches!(ty.clone().into_iter().next(), Some(TokenTree::Ident(ident)) if ident == "impl")
+}
+
+/// Message for a construction failure of `field`, naming the struct so that a panic can be traced
+/// back to the type that failed to build, e.g. ``Pool: `size` must be non-zero``.
+fn failure_message(name: &Ident, field: &Field, failure: &str) -> String {
+    let field = field
+        .ident
+        .as_ref()
+        .map(|ident| ident.unraw().to_string())
+        .unwrap_or_default();
+    format!("{name}: `{field}` {failure}")
+}
+
+/// Name of the parameter a field is passed through, converted to the `rename_all` case if set.
+fn param_name(ident: &Ident, case: Option<Case>) -> Ident {
+    let Some(case) = case else {
+        return ident.clone();
+    };
+    let renamed = case.apply(&ident.unraw().to_string());
+    // Keywords such as `type` need the raw form to be usable as a parameter name.
+    match syn::parse_str::<Ident>(&renamed) {
+        Ok(_) => Ident::new(&renamed, ident.span()),
+        Err(_) => Ident::new_raw(&renamed, ident.span()),
+    }
+}
+
+/// Returns `true` if `expr` uses the `?` operator outside of a nested closure or async block.
+fn contains_try(expr: &Expr) -> bool {
+    struct Finder(bool);
+
+    impl<'ast> Visit<'ast> for Finder {
+        fn visit_expr_try(&mut self, _: &'ast ExprTry) {
+            self.0 = true;
+        }
+
+        fn visit_expr_closure(&mut self, _: &'ast ExprClosure) {}
+
+        fn visit_expr_async(&mut self, _: &'ast ExprAsync) {}
+    }
+
+    let mut finder = Finder(false);
+    finder.visit_expr(expr);
+    finder.0
+}
+
+/// Returns `true` if `expr` uses `.await` outside of a nested closure or async block.
+fn contains_await(expr: &Expr) -> bool {
+    struct Finder(bool);
+
+    impl<'ast> Visit<'ast> for Finder {
+        fn visit_expr_await(&mut self, _: &'ast ExprAwait) {
+            self.0 = true;
+        }
+
+        fn visit_expr_closure(&mut self, _: &'ast ExprClosure) {}
+
+        fn visit_expr_async(&mut self, _: &'ast ExprAsync) {}
+    }
+
+    let mut finder = Finder(false);
+    finder.visit_expr(expr);
+    finder.0
+}
+
+/// Returns `true` if `expr` calls a function, method or macro, which may not be const.
+fn contains_call(expr: &Expr) -> bool {
+    struct Finder(bool);
+
+    impl<'ast> Visit<'ast> for Finder {
+        fn visit_expr_call(&mut self, _: &'ast ExprCall) {
+            self.0 = true;
+        }
+
+        fn visit_expr_method_call(&mut self, _: &'ast ExprMethodCall) {
+            self.0 = true;
+        }
+
+        fn visit_macro(&mut self, _: &'ast Macro) {
+            self.0 = true;
+        }
+    }
+
+    let mut finder = Finder(false);
+    finder.visit_expr(expr);
+    finder.0
+}
+
+/// Returns `true` if `tokens` contain `ident` at any depth, or a literal containing its name, as
+/// a format string capturing it would. A conservative stand-in for whether an expression refers
+/// to the local of that name: only uses that cannot be the local are skipped, namely a call
+/// `ident(...)`, a macro `ident!`, and a path segment or member after `::` or `.`.
+fn mentions(tokens: &TokenStream, ident: &Ident) -> bool {
+    let trees = tokens.clone().into_iter().collect::<Vec<_>>();
+    trees.iter().enumerate().any(|(i, tree)| match tree {
+        TokenTree::Ident(other) => {
+            let punct = |back: usize, c: char| {
+                i >= back && matches!(&trees[i - back], TokenTree::Punct(p) if p.as_char() == c)
+            };
+            let after_path = punct(1, '.') || (punct(1, ':') && punct(2, ':'));
+            let called = match trees.get(i + 1) {
+                Some(TokenTree::Group(group)) => group.delimiter() == Delimiter::Parenthesis,
+                Some(TokenTree::Punct(p)) => p.as_char() == '!',
+                _ => false,
+            };
+            other.unraw() == ident.unraw() && !after_path && !called
+        }
+        TokenTree::Group(group) => mentions(&group.stream(), ident),
+        TokenTree::Literal(literal) => literal.to_string().contains(&ident.unraw().to_string()),
+        TokenTree::Punct(_) => false,
+    })
+}
+
+/// Why a field converted by `conversion` keeps the constructor from being a `const fn`.
+fn conversion_blocker(conversion: &str) -> &'static str {
+    match conversion {
+        "from_iter" => "`from_iter` collects an iterator",
+        "try" => "`try` parameters are propagated with `?`",
+        "init" => "`init` calls a closure",
+        "unbox" => "`unbox` moves out of a `Box`",
+        "clamp" => "`clamp` calls `clamp`, `max` or `min`, which may not be const",
+        "intern" => "`intern` calls the interner, which may not be const",
+        "as_ref" => "`as_ref` calls `AsRef::as_ref` and `ToOwned::to_owned`, which are not const",
+        _ => "`nonzero` is checked with `TryFrom` under `try_new`",
+    }
+}
+
+/// Returns `true` for literal expressions, including negated numeric literals like `-1`.
+fn is_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(_) => true,
+        Expr::Unary(unary) => matches!(unary.op, syn::UnOp::Neg(_)) && is_literal(&unary.expr),
+        Expr::Paren(paren) => is_literal(&paren.expr),
+        _ => false,
+    }
+}
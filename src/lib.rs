@@ -60,70 +60,497 @@
 //! }
 //! ```
 //!
+//! ### Example with Field Defaults
+//!
+//! ```rust
+//! use impl_new_derive::ImplNew;
+//!
+//! #[derive(ImplNew)]
+//! struct Connection {
+//!     pub host: String,
+//!     #[new(default(5432))]
+//!     pub port: u16,
+//!     #[new(default(vec!["ssl".to_string()]))]
+//!     options: Vec<String>,
+//! }
+//!
+//! fn main() {
+//!     let connection = Connection::new("localhost".to_string());
+//!     assert_eq!(connection.port, 5432);
+//!     assert_eq!(connection.options, ["ssl"]);
+//! }
+//! ```
+//!
+//! A field with a default is no longer a parameter, even if it is public. The older
+//! `#[default(expr)]` form behaves the same, but is deprecated in favor of `#[new(default(expr))]`:
+//!
+//! ```rust
+//! # #![allow(deprecated)]
+//! use impl_new_derive::ImplNew;
+//!
+//! #[derive(ImplNew)]
+//! struct Retry {
+//!     pub attempts: u32,
+//!     #[default(std::time::Duration::from_millis(250))]
+//!     backoff: std::time::Duration,
+//! }
+//!
+//! fn main() {
+//!     let retry = Retry::new(3);
+//!     assert_eq!(retry.backoff.as_millis(), 250);
+//! }
+//! ```
+//!
+//! ## Attributes
+//!
+//! The generated constructor can be tuned with a struct-level `#[new(...)]` attribute:
+//!
+//! - `#[new(private)]`: emits `fn new` without `pub`, so the constructor is only usable inside the
+//!   module that defines the struct.
+//! - `#[new(trace)]`: logs every call to `new` with `log::trace!`, including the `Debug`
+//!   representation of each argument. Use `#[new(trace = "tracing")]` to log through
+//!   `tracing::trace!` instead. All parameters must implement `Debug`.
+//! - `#[new(free_fn = "name")]`: emits the constructor as a module-level function `name` returning
+//!   the struct instead of an inherent `new` method. Since there is no `impl` block, `Self` is not
+//!   available in `#[new(default(...))]` expressions.
+//! - `#[new(try_new)]`: generates a fallible `try_new` returning `Result<Self, E>` instead of
+//!   `new`. `#[new(default(...))]` expressions may then use the `?` operator; outside of this mode
+//!   such defaults are rejected. `E` is `Box<dyn std::error::Error + Send + Sync>` unless set with
+//!   `#[new(error = "MyError")]`.
+//! - `#[new(fallible)]`: the same fallible constructor under the name `new`, for APIs that reserve
+//!   an error channel before they validate anything. `E` defaults to `std::convert::Infallible`,
+//!   and callers already handle the `Result` when validation is added later. Every option that
+//!   requires `try_new` accepts `fallible` as well.
+//! - `#[new(extern_c)]`: generates the constructor as an `extern "C" fn`, for constructors exposed
+//!   over FFI, typically on a `#[repr(C)]` struct. The macro does not check that the parameters
+//!   and the return type are FFI-safe; the compiler's `improper_ctypes_definitions` lint does.
+//! - `#[new(resource(arena: &mut Arena))]`: adds the listed `name: Type` parameters in front of
+//!   the field parameters, for resources such as an arena or a pool that the field defaults draw
+//!   from, e.g. `#[new(default(arena.alloc(label)))]`. They are not stored in the struct. Several
+//!   defaults may use a `&mut` resource in turn, since each default is bound to a local before the
+//!   next one is computed. `from_map`, `with_overrides`, the builders, `macro` and the struct-level
+//!   `default` do not take the resources and cannot be combined with them.
+//! - `#[new(const)]`: generates `const fn new`, usable in `const` and `static` items. Every
+//!   field that is not a parameter needs a const `#[new(default(...))]`, and options that are not
+//!   const, such as `trace`, `from_iter` or `Default::default()` fields, are rejected. With
+//!   `#[new(const, try_const)]`, the constructor is only `const` when nothing rules it out and no
+//!   default calls a function, which the macro cannot tell to be const; otherwise it silently
+//!   falls back to a plain `fn new`.
+//! - `#[new(async)]`: generates the constructor as an `async fn`, so `#[new(default(...))]`
+//!   expressions may use `.await`; outside of this mode such defaults are rejected. It combines
+//!   with `try_new` into an `async fn try_new`, and makes the `typed_builder`'s `build` async.
+//! - `#[new(inline)]`, `#[new(inline = "always")]`, `#[new(inline = "never")]`: adds the matching
+//!   `#[inline]` attribute to the constructor.
+//! - `#[new(no_must_use)]`: omits the `#[must_use]` attribute the constructor carries by default.
+//! - `#[new(hot)]`: shorthand for `#[new(inline = "always", no_must_use)]`, for small types built in
+//!   tight loops.
+//! - `#[new(doc_defaults)]`: adds a `# Defaults` section to the constructor's documentation listing
+//!   each field that is not a parameter together with its `Default::default()` or
+//!   `#[new(default(...))]` initializer.
+//! - `#[new(doc_args)]`: documents every parameter with its type in an `# Arguments` section,
+//!   followed by the `# Defaults` section of `doc_defaults`. Types are shown as written on the
+//!   field, or as the generated parameter type for converting options such as `from_iter`.
+//! - `#[new(metadata)]`: also generates `pub const FIELD_COUNT: usize` and
+//!   `pub const FIELD_NAMES: &[&str]` listing every field of the struct in declaration order.
+//! - `#[new(macro)]`: also generates a `macro_rules!` named after the struct in snake_case, so
+//!   `MyStruct` can be built with `my_struct! { age: 30, name: "John".to_string() }`. Arguments may
+//!   be given in any order and are forwarded to the constructor positionally. Like any
+//!   `macro_rules!`, the macro is available in the defining module after the struct definition.
+//! - `#[new(where = "T: Send + 'static")]`: adds the given predicates to the where-clause of the
+//!   generated impl, so construction can require bounds the struct itself does not.
+//! - `#[new(expose_defaults)]`: for every field with a literal default such as
+//!   `#[new(default(30))]`, generates an associated constant named after the field, e.g.
+//!   `pub const TIMEOUT_DEFAULT: u64 = 30;`.
+//! - `#[new(eager_default)]`: hoists every literal field default into a hidden associated constant
+//!   that the constructor reads, such as `__DEFAULT_TIMEOUT`. Other defaults stay inline.
+//! - `#[new(self_ty = "Alias")]`: writes the generated impl for the given type instead of the
+//!   struct's own name, e.g. a type alias of a struct whose name was generated by another macro.
+//!   The type must resolve to the struct itself. The `#[new(macro)]` macro is then named after it.
+//! - `#[new(also_impl_for = "FooU32 = Foo<u32>")]`: also declares `type FooU32 = Foo<u32>;`,
+//!   with the struct's visibility, so the common instantiation is constructed as `FooU32::new(..)`
+//!   even when the arguments do not determine its type parameters. A second inherent `new` on
+//!   `Foo<u32>` would clash with the generic one, so the alias reaches the generic constructor.
+//!   Repeat the option for several instantiations.
+//! - `#[new(const_default)]`: also generates `pub const DEFAULT: Self` built from the field
+//!   defaults. Every field needs a const-evaluable `#[new(default(...))]` (or be `PhantomData`), and
+//!   none may be a constructor parameter.
+//! - `#[new(rename_all = "camelCase")]`: converts the parameter names of the constructor to the
+//!   given case, one of `snake_case`, `camelCase`, `PascalCase` or `SCREAMING_SNAKE_CASE`, for
+//!   generated bindings that follow another naming convention. The fields keep their names.
+//! - `#[new(post = "value.register()")]`: evaluates the expression after the struct is built and
+//!   before it is returned, with the new value bound to a mutable `value`. Under `try_new`, the
+//!   expression may use `?` to reject the value.
+//! - `#[new(pinned)]`: returns the new value as `Pin<Box<Self>>` built with `Box::pin`, for
+//!   self-referential or async types. Combined with `try_new`, the result is
+//!   `Result<Pin<Box<Self>>, E>`.
+//! - `#[new(validate_fn = "path")]`: with `try_new`, passes the constructed value to
+//!   `path(&value) -> Result<(), E>` and returns its error, so invariants spanning several fields
+//!   are checked in one place.
+//! - `#[new(assert_invariants)]`: calls `value.invariants()` on the constructed value in a
+//!   `debug_assert!`, so `new` panics in debug builds if it returns `false` and the check compiles
+//!   away in release builds. `assert_invariants = "is_valid"` calls another method instead.
+//! - `#[new(wrap = "path", returns = "Type")]`: passes the constructed value through `path(value)`
+//!   and returns its result, e.g. `wrap = "Arc::new", returns = "Arc<Self>"` or a function that
+//!   registers the value first. `returns` is required, since Rust cannot infer a function's return
+//!   type.
+//! - `#[new(impl_default)]`: also implements `Default` by calling the constructor, which must not
+//!   take any parameters. Without it, a constructor with no parameters is marked
+//!   `#[allow(clippy::new_without_default)]` instead.
+//! - `#[new(profile(name = "fast", defaults(cap = 16, label = "fast")))]`: also generates a preset
+//!   constructor `fast()` without parameters. It calls the constructor with the profile's values
+//!   for the parameters, which it must all give, then assigns the values it gives other fields,
+//!   overriding their defaults. Under `optional_params`, a value is passed as `Some(value)`, and
+//!   an optional parameter the profile leaves out is passed as `None`. Repeat the option for more
+//!   profiles. A profile cannot be combined with `try_new`, `pinned`, `wrap`, `async` or
+//!   `resource`, and can only set fields that are not parameters when there is no `post`,
+//!   `assert_invariants` or `timed` field, which would run before the assignment. Clippy's
+//!   `duplicated_attributes` lint may mistake the same field set to different integers in two
+//!   profiles for a duplicate.
+//! - `#[new(singleton)]`: also generates `instance() -> &'static Self`, returning a shared value
+//!   created with the constructor on first access and kept in a `std::sync::LazyLock`, for global
+//!   configuration types. The constructor must take no parameters, and the struct must not be
+//!   generic and must be `Send + Sync`.
+//! - `#[new(from)]`: also implements `From<T>` by calling a constructor that takes exactly one
+//!   parameter, so a newtype such as a `#[repr(transparent)]` wrapper works with `.into()` and
+//!   generic `impl Into<Wrapper>` arguments. A grouped parameter gives `From<(A, B)>`.
+//! - `#[new(marker = "path::Trait")]`: also writes an empty `impl path::Trait for Struct`, with
+//!   the same generics and bounds as the constructor's impl, so frameworks can bound on types
+//!   with a derived constructor. The trait must have no items without defaults. Repeat the option
+//!   for several traits. The impls are for the deriving struct, which is local, so traits from
+//!   other crates are allowed by the orphan rule.
+//! - `#[new(deref)]`: also implements `Deref` and `DerefMut` to the struct's only field, so a
+//!   newtype exposes the methods of the type it wraps. A struct with any other number of fields
+//!   is an error.
+//! - `#[new(default)]`: generates `new()` as an alias of `Default::default()`, for types whose
+//!   `new` should mean "default". The struct must implement `Default`. Fields with their own
+//!   `#[new(default(expr))]` are set to `expr`, with the rest taken from `Default::default()`.
+//!   Only `private`, `no_must_use`, `inline`, `where`, `self_ty`, `cfg`, `const` with `try_const`
+//!   and `ignore_unknown` apply to such a `new()`; any other struct option is an error, as is any
+//!   field option other than `default`.
+//! - `#[new(builder)]`: splits public fields into required and optional ones. Fields without a
+//!   default stay constructor parameters; each public field with `#[new(default(...))]` or
+//!   `#[new(default)]` gets a chainable `with_<field>(self, value) -> Self` method instead, as in
+//!   `Server::new(host).with_port(443)`.
+//!   Required fields are thus enforced at compile time by the parameters of `new`, so there is no
+//!   separate `build()` step that could fail. Add `builder_into`, as in
+//!   `#[new(builder, builder_into)]`, to take the setter arguments as `impl Into<T>`, e.g.
+//!   `.with_name("x")` for a `String` field.
+//! - `#[new(typed_builder)]`: also generates a type-state `<Struct>Builder`, started with
+//!   `Struct::builder()`, with one setter per constructor parameter. Its `build()` only exists
+//!   once every parameter has been set, so a missing required field is a compile error rather
+//!   than a runtime one, and it returns whatever the constructor returns. Fields that are not
+//!   parameters keep their defaults. Parameters with an `impl Trait` type, such as those of
+//!   `from_iter`, `try`, `init` or `as_ref`, cannot be stored in the builder and are rejected, as
+//!   are `str_params`, `wrap` and `generics_on_fn`.
+//! - `#[new(checked_builder)]`: generates a `<Struct>Builder` that checks for missing parameters
+//!   at run time instead. It is a single type, so it can be stored or filled in conditionally, and
+//!   its `build()` returns `Result<Struct, <Struct>BuilderError>`. The error enum has one variant
+//!   per parameter, such as `ServerBuilderError::MissingHost`, and `build()` reports the first
+//!   unset one in parameter order. It has the same restrictions as `typed_builder`, and cannot be
+//!   combined with it or with `try_new`.
+//! - `#[new(prefix = "Internal")]`: prepends the prefix to the names of the types the builders
+//!   generate, giving `InternalServerBuilder` and `InternalServerBuilderError`, for when the
+//!   default names would collide with other items. Methods such as `builder()` keep their names.
+//! - `#[new(expose_builder_fn = "configure")]`: names the function starting a `typed_builder` or
+//!   `checked_builder`, giving `Server::configure()` in place of `Server::builder()`.
+//! - `#[new(ignore_unknown)]`: turns unknown keys, in the struct and field attributes, from errors
+//!   into warnings, for code shared with newer versions of the macro that accept more options.
+//!   Options in the wrong position are still errors.
+//! - `#[new(test_full)]`: also generates a `#[cfg(test)]` `new_full` taking every field in
+//!   declaration order, private ones included, so unit tests can build arbitrary states without
+//!   widening field visibility. `new` is unaffected.
+//! - `#[new(str_params)]`: takes every `String` parameter as `&str` and stores it with `to_owned`,
+//!   so callers can pass string literals. A field marked `#[new(no_str)]` keeps its `String`
+//!   parameter.
+//! - `#[new(generics_on_fn)]`: moves type parameters used only by defaulted fields from the impl
+//!   onto `new`, so `Cache<K, V>` with `pub key: K` and a private `entries: Vec<V>` is built with
+//!   `Cache::new::<V>(key)` or from an annotated binding. The moved parameters may not have bounds,
+//!   since the constructor is written in an impl for the struct with `()` in their place.
+//!   Without it, every type parameter lives on the impl: those used by parameters are inferred from
+//!   the arguments, as in `Pair::new(1u8, "a")`, and the rest are named with a turbofish on the
+//!   type, as in `Cache::<String, u64>::new(key)`, or inferred from an annotated binding.
+//! - `#[new(with_overrides)]`: also generates `with(base: Self, ...)`, taking the same parameters
+//!   as the constructor and filling every other field from `base`, like a typed struct update
+//!   `Self { name, ..base }`. Checks such as `non_empty` and `regex` run on its parameters as
+//!   they do in the constructor.
+//! - `#[new(sorted_params)]`: orders the constructor parameters alphabetically by field name
+//!   instead of declaration order, for generated code whose signatures should not change when
+//!   fields are reordered.
+//! - `#[new(field_order_check)]`: fails to compile unless the parameters come in the order the
+//!   fields are declared in, naming the first field that is out of place. `sorted_params` and a
+//!   group, which takes the place of its first member, are what can move a field.
+//! - `#[new(as_tuple_args)]`: takes every parameter together as one tuple, as in
+//!   `new((name, age): (String, u32))`, for generic code that forwards arguments as a tuple.
+//!   Fields cannot also be grouped with `#[new(group = "...")]`.
+//! - `#[new(from_map)]`: also generates
+//!   `from_map(&HashMap<String, String>) -> Result<Self, Box<dyn Error + Send + Sync>>`, which
+//!   parses every parameter with `FromStr` from the entry named after its field and defaults the
+//!   other fields as `new` does. A missing entry or a parse failure is an error naming the field.
+//!   The value then goes through the same checks, `post`, `validate_fn` and `assert_invariants`
+//!   as in `new`, except that a failed check such as `non_empty` is returned as the error instead
+//!   of panicking.
+//! - `#[new(params(a, b))]`: makes exactly the listed fields constructor parameters, whatever
+//!   their visibility, and defaults every other field, so a mostly-defaulted struct names its
+//!   inputs in one place. Parameters keep declaration order, and a listed field with a default
+//!   is still defaulted. Listing a field the struct does not have is an error.
+//! - `#[new(copy_ctor)]`: also generates `from_ref(other: &Self) -> Self`, returning `*other`,
+//!   for value types that derive `Copy`.
+//! - `#[new(from_json)]`: also generates `from_json(json: &str) -> Result<Self, serde_json::Error>`,
+//!   deserializing the struct with `serde_json::from_str`. It needs the `serde` feature of this
+//!   crate, and is gated on a `serde` feature of the deriving crate, which must depend on `serde`
+//!   and `serde_json` when the feature is enabled, typically forwarding it as
+//!   `serde = ["dep:serde", "dep:serde_json", "impl-new-derive/serde"]`. The struct must implement
+//!   `Deserialize`.
+//! - `#[new(optional_params)]`: public fields with a default become `Option<T>` parameters
+//!   instead of being left out: `Some(value)` sets the field and `None` falls back to its
+//!   default. Fields without a default stay plain `T` parameters. The `Option` parameters cannot
+//!   take conversions or checks such as `non_empty` and `regex`.
+//! - `#[new(zeroed)]`: initializes the fields that would use `Default::default()` with
+//!   `bytemuck::Zeroable::zeroed()` instead, for plain-old-data structs whose large arrays do not
+//!   implement `Default`. It needs the `bytemuck` feature of this crate, the deriving crate must
+//!   depend on `bytemuck`, and the field types must implement `Zeroable`.
+//! - `#[new(defaults_from = "DEFAULTS")]`: initializes the named fields that would use
+//!   `Default::default()` from the like-named fields of a constant instead, e.g. `timeout` from
+//!   `DEFAULTS.timeout`, so several structs can share one profile of defaults. The constant must
+//!   have a field of a matching type for each such field.
+//! - `#[new(cfg = "feature = \"ctor\"")]`: gates every generated item behind the given `#[cfg(...)]`
+//!   predicate, so the constructor only exists when, for example, a feature of the deriving crate
+//!   is enabled.
+//!
+//! ```rust
+//! mod shapes {
+//!     use impl_new_derive::ImplNew;
+//!
+//!     #[derive(ImplNew)]
+//!     #[new(private)]
+//!     pub struct Circle {
+//!         pub radius: f64,
+//!     }
+//!
+//!     pub fn unit() -> Circle {
+//!         Circle::new(1.0)
+//!     }
+//! }
+//!
+//! fn main() {
+//!     assert_eq!(shapes::unit().radius, 1.0);
+//! }
+//! ```
+//!
+//! Individual fields accept a field-level `#[new(...)]` attribute:
+//!
+//! - `#[new(default(expr))]`: the field is initialized with `expr` instead of `Default::default()`.
+//!   This also applies to public fields, which are then no longer constructor parameters. The
+//!   expression is emitted verbatim inside the generated `impl`, so it may call other constructors
+//!   or associated functions of `Self`. The attribute may be wrapped in `cfg_attr`, e.g.
+//!   `#[cfg_attr(feature = "fast", new(default(FastImpl::new())))]`, to select a default per
+//!   configuration; the compiler resolves `cfg_attr` before the derive runs. The expression is
+//!   checked against the field's type, so constants and array repeat expressions such as
+//!   `#[new(default([0; 4096]))]` work for types like large arrays that do not implement `Default`;
+//!   one that calls nothing and mentions no parameter or field is written into the struct literal
+//!   directly, without a local to move out of. Defaults are computed in field order before any
+//!   parameter is moved into the struct, so an expression may borrow the parameters and the
+//!   defaulted fields declared before it, e.g. `#[new(default(checksum(&name, age)))]`. This order
+//!   is guaranteed: every parameter is in scope wherever it is declared, each defaulted field is in
+//!   scope, under its own name, for the defaults declared after it and nowhere else, and later
+//!   defaults are not. Const generic parameters are in scope, so `#[new(default([[0u8; W]; H]))]`
+//!   works for a `Grid<const W: usize, const H: usize>`. Tuple fields take a parenthesized tuple
+//!   expression, e.g. `#[new(default((1, "x".to_string())))]`. Any expression works, including
+//!   field accesses on a shared constant, so related structs can draw their defaults from one
+//!   place: `#[new(default(DEFAULTS.timeout))]`. Macro calls such as
+//!   `#[new(default(env!("BUILD_ID").to_string()))]` or `include_str!` and `include_bytes!` bake
+//!   values in at compile time, with file paths relative to the file the attribute is written in,
+//!   while `cfg!` conditions such as
+//!   `#[new(default(if cfg!(debug_assertions) { 100 } else { 1000 }))]` pick a value with an
+//!   ordinary `if`, unlike `cfg_attr`, which removes the attribute itself. Conversions such as
+//!   `#[new(default("x".into()))]` infer their target from the field type. The older
+//!   `#[default(expr)]` attribute is still accepted, but deprecated, as it can clash with other
+//!   derives.
+//! - `#[new(default)]`: a public field is initialized with `Default::default()` instead of being a
+//!   parameter.
+//! - `#[new(nested_default)]`: initializes the field with its type's own parameterless `new()`,
+//!   such as one generated by `ImplNew` for a struct whose fields all have defaults. Use
+//!   `#[new(default(Inner::new(...)))]` to pass arguments instead.
+//! - `#[new(default_fn = "Vec::new")]`: initializes the field by calling the given function or
+//!   associated function without arguments, the same as `#[new(default(Vec::new()))]`. The path
+//!   may carry generic arguments or a qualified self type, e.g. `"<u16 as Default>::default"`.
+//! - `#[new(rename = "kind")]`: names the constructor parameter `kind` while still assigning to
+//!   the field, which helps with raw identifiers such as `r#type`. Keywords are accepted and become
+//!   raw parameters, e.g. `rename = "type"`. It takes precedence over `rename_all`.
+//! - `#[new(group = "name")]`: public fields sharing a group name are passed together as a single
+//!   tuple parameter, placed where the first field of the group would appear.
+//! - `#[new(skip_if_default)]`: the same as `#[new(default)]`. Useful for zero-sized markers such
+//!   as unit structs. `PhantomData` and `PhantomPinned` fields are handled this way
+//!   automatically, whatever their visibility.
+//! - `#[new(try)]`: with `#[new(try_new)]`, the parameter becomes `Result<T, impl Into<E>>` and
+//!   `try_new` returns early with the converted error if it is an `Err`.
+//! - `#[new(from_iter)]`, or its alias `#[new(collect)]`: the parameter becomes
+//!   `impl IntoIterator<Item = T>` and is collected into the field, which must implement
+//!   `FromIterator`. The item is `T` for collections like `Vec<T>` or `HashSet<T>` and `(K, V)`
+//!   for maps like `HashMap<K, V>`. This makes even a private field a parameter.
+//! - `#[new(lock)]`: for a `Mutex<T>` or `RwLock<T>` field, such as `std::sync::Mutex<Vec<u8>>`,
+//!   the parameter becomes the inner `T` and is wrapped with `Mutex::new` or `RwLock::new`. The
+//!   wrapper is recognized by its name, so other locks with a `new(T)` constructor work as well.
+//! - `#[new(cell)]`: the same for a `Cell<T>` or `RefCell<T>` field, wrapping the parameter with
+//!   `Cell::new` or `RefCell::new`.
+//! - `#[new(nonzero)]`: for a `NonZeroU32`-style or `NonZero<T>` field, the parameter becomes the
+//!   raw integer. `new` panics if it is zero, with a message naming the struct and the field such
+//!   as ``Pool: `size` must be non-zero``; `try_new` returns the `TryFromIntError` converted
+//!   into its error type instead.
+//! - `#[new(from_millis)]`: for a `Duration` field, the parameter becomes a `u64` count of
+//!   milliseconds and the field is built with `Duration::from_millis`. `from_secs`, `from_micros`
+//!   and `from_nanos` work the same way for the other units.
+//! - `#[new(unbox)]`: the parameter becomes `Box<T>` and the field is initialized with its
+//!   contents, for callers that already hold the value boxed.
+//! - `#[new(intern = "interner")]`: the parameter becomes a `&str`, and the field is initialized
+//!   with `interner.intern(param)`, where `interner` is one of the struct's `resource` parameters,
+//!   e.g. `#[new(resource(interner: &mut Interner))]`. The field's type is whatever `intern`
+//!   returns, typically a symbol.
+//! - `#[new(as_ref = "Path")]`: the parameter becomes `impl AsRef<Path>`, and the field is
+//!   initialized with `param.as_ref().to_owned()`, so a `PathBuf` field accepts a `&str`, a
+//!   `String` or a `&Path`. The field's type must be the target's owned form, e.g. `String` for
+//!   `as_ref = "str"`.
+//! - `#[new(clamp = "0..=100")]`: clamps the parameter into the inclusive range instead of
+//!   rejecting values outside it, initializing the field with `param.clamp(0, 100)`. `"1.."` and
+//!   `"..=9"` clamp on one side only, with `max` and `min`. The field type must have these
+//!   methods, as `Ord` types and floats do.
+//! - `#[new(param_ty = "PathBuf", convert(Arc::from(path)))]`: takes the parameter as the given
+//!   type and stores the result of the `convert` expression, which refers to the parameter by its
+//!   name, in the field. Either half works alone: `param_ty` converts with `Into::into`, e.g.
+//!   `param_ty = "&str"` for a `String` field, and `convert` keeps the field's type for the
+//!   parameter, e.g. `convert(tag.to_lowercase())`. Under `try_new`, `convert` may use `?`.
+//! - `#[new(caller_location)]`: for a `&'static Location<'static>` field, records where the value
+//!   was created. The constructor gets `#[track_caller]` and initializes the field with
+//!   `Location::caller()`, so it points at the call to `new`, even for a public field, which is
+//!   never a parameter. `async` and `extern_c` constructors cannot track their caller.
+//! - `#[new(timed)]`: for a `Duration` field, records how long the constructor took, from before
+//!   the checks and defaults to after `post`, `validate_fn` and `assert_invariants`. The field is
+//!   never a parameter; it starts out as `Duration::ZERO` and is set from an `Instant` once the
+//!   value is complete. `from_map` is timed the same way, from before it parses the map.
+//! - `#[new(type_name)]`: initializes the field with the struct's name, e.g. `"Worker"`, for
+//!   logging and diagnostics. The field must be a string type such as `&'static str`, `String` or
+//!   `Arc<str>`, and is never a parameter.
+//! - `#[new(non_empty)]`: rejects an empty parameter, checked with its `is_empty()` method, so it
+//!   works for `Vec`, `String`, `HashMap` and other collections. `new` panics with a message
+//!   naming the field, and `try_new` returns that message as the error, which `E` must be
+//!   convertible from.
+//! - `#[new(regex = "^[a-z]+$")]`: rejects a string parameter that does not match the pattern,
+//!   the same way as `non_empty`, with a message such as ``User: `name` does not match
+//!   `^[a-z]+$` ``. The pattern is compiled with `regex::Regex` the first time the constructor
+//!   runs and kept in a `LazyLock`; an invalid pattern panics then. It needs the `regex` feature
+//!   of this crate, and the deriving crate must depend on `regex`.
+//! - `#[new(param_attr = "allow(non_snake_case)")]`: puts the attributes, separated by commas, on
+//!   the constructor parameter, e.g. lint levels or a `#[doc]`. A group parameter gets the
+//!   attributes of all its fields, since a tuple pattern's elements cannot carry attributes.
+//! - `#[new(init)]`: the field, public or private, is initialized by calling a closure parameter
+//!   `<field>_init: impl FnOnce() -> T`, for values that depend on context only the caller has.
+//!   `#[new(init = "name")]` names the parameter explicitly.
+//! - `#[new(flatten(x: f32, y: f32))]`: the listed parameters take the place of the field, which is
+//!   then built with its own `new(x, y)`, e.g. a field whose type also derives `ImplNew`. The
+//!   macro cannot see the fields of another struct, so the parameters and their types are listed
+//!   explicitly, in the order the inner `new` takes them.
+//!
+//! When several defaults apply to one field, the most specific one wins:
+//!
+//! 1. A field's `#[new(default(expr))]`, or the deprecated `#[default(expr)]`, always initializes
+//!    it with `expr`. It overrides a bare `#[new(default)]` on the same field as well as the
+//!    struct-level `#[new(default)]`, `#[new(zeroed)]` and `#[new(defaults_from)]`. Giving a field
+//!    both forms is an error.
+//! 2. Otherwise, a field that is not a parameter, including one with a bare `#[new(default)]`,
+//!    takes the struct-level fallback: its own part of `Default::default()` under the
+//!    struct-level `#[new(default)]`, `Zeroable::zeroed()` under `#[new(zeroed)]`, the constant's
+//!    like-named field under `#[new(defaults_from)]`, and `Default::default()` for its type if
+//!    none is set.
+//!
+//! Every option can also be written under the `impl_new` namespace, as in
+//! `#[impl_new(default(8))]`, for structs that also derive macros claiming or stripping `new` or
+//! `default` attributes. Both forms may be mixed on one struct.
+//!
+//! Unknown options are rejected at compile time. The error lists the options valid in that
+//! position, suggests the closest one for a likely typo such as `#[new(defualt(1))]`, and points
+//! out struct-level options used on a field and vice versa.
+//!
+//! ```rust
+//! use impl_new_derive::ImplNew;
+//!
+//! #[derive(ImplNew)]
+//! struct Point {
+//!     #[new(group = "pos")]
+//!     pub x: f32,
+//!     #[new(group = "pos")]
+//!     pub y: f32,
+//!     pub label: String,
+//! }
+//!
+//! fn main() {
+//!     let point = Point::new((1.0, 2.0), "origin".to_string());
+//!     assert_eq!((point.x, point.y), (1.0, 2.0));
+//! }
+//! ```
+//!
 //! ## How It Works
 //!
 //! When the `ImplNew` macro is applied to a struct, the macro performs the following actions:
 //! - Iterates over the struct's fields.
 //! - Public fields are added as parameters to the generated `new` function.
+//!   Only plain `pub` counts as public: fields with a restricted visibility such as `pub(crate)`,
+//!   `pub(super)`, `pub(in path)` or `pub(self)` are treated like private fields.
 //! - Non-public fields are initialized with `Default::default()`.
+//!   This is the `Default` of each field's type: the struct's own `Default` impl, derived or
+//!   written by hand, is never consulted, so `new` and `default()` may disagree on a field. Use
+//!   the struct-level `#[new(default)]` to build `new()` from the struct's `Default` instead.
+//! - `PhantomData` and `PhantomPinned` fields are initialized with their unit value and never
+//!   become parameters. They add no bounds, so a type parameter used only in a `PhantomData<T>`
+//!   stays on the impl unconstrained. The arguments cannot determine it, so callers name it with a
+//!   turbofish such as `Id::<User>::new(7)` or a type annotation.
+//! - Non-public `OnceCell<T>` and `OnceLock<T>` fields start out empty through their own `new()`,
+//!   recognized by the type's name, so lazily initialized fields need no `Default` and the
+//!   constructor can still be `const`.
 //! - If the struct contains generics, the macro correctly handles them in the `impl` block.
+//! - The generated `impl` is marked `#[automatically_derived]`, like those of the standard derives.
+//! - The constructor, the `with_<field>` setters of `builder`, the builder types and the
+//!   `typed_builder`'s `build()` are `#[must_use]`, so a value built and then dropped is reported.
+//!   Functions returning a `Result` rely on its own `#[must_use]`.
+//! - A constructor taking more than seven parameters allows `clippy::too_many_arguments`, which
+//!   would otherwise fire on code the caller cannot annotate.
+//! - The `impl` is emitted right next to the struct and names it by its bare identifier, which
+//!   always resolves there, so structs in nested modules or function bodies need no full path.
 //!
 //! ## Limitations
 //! - The `ImplNew` macro only works for structs with named fields.
-//! - Private fields must implement `Default`, or the macro will fail to compile.
+//! - Private fields without a `#[new(default(...))]` attribute must implement `Default`, or the
+//!   macro will fail to compile. This includes collections with a custom allocator, such as a
+//!   `Vec<T, A>` under the unstable `allocator_api`, which only implement `Default` for the global
+//!   allocator. Pass the allocator in with `#[new(resource(alloc: A))]` and
+//!   `#[new(default(Vec::new_in(alloc)))]` instead. For a private field whose type is a bare type
+//!   parameter `T`, the impl gets a `T: Default` bound instead, so the constructor is only
+//!   available for such `T`. Mark the field `#[new(skip_default_bound)]` to leave the bound out
+//!   when the struct's own bounds already imply it, e.g. `T: Config` where `Config` has `Default`
+//!   as a supertrait.
+//! - Parameters are taken by value, so public fields of an unsized type such as `str`, `[T]` or
+//!   `dyn Trait` are rejected with a suggestion to use a reference, a `Box` or an owned type.
 //!
 //! ## License
 //!
 //! Licensed under the MIT License.
 extern crate proc_macro;
 
+mod attr;
+mod case;
+mod checked_builder;
+mod docs;
+mod expand;
+mod named_args;
+mod ty;
+mod typed_builder;
+
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput, FieldsNamed, Visibility};
+use syn::{parse_macro_input, DeriveInput};
 
-#[proc_macro_derive(ImplNew)]
+#[proc_macro_derive(ImplNew, attributes(new, impl_new, default))]
 pub fn derive_impl_new(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let name = input.ident;
-    let generics = input.generics;
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-
-    let fields = if let syn::Data::Struct(data) = input.data {
-        if let syn::Fields::Named(FieldsNamed { named, .. }) = data.fields {
-            named
-        } else {
-            panic!("`ImplNew` macro can only be used on structs with named fields");
-        }
-    } else {
-        panic!("`ImplNew` macro can only be used on structs");
-    };
-
-    let pub_fields = fields
-        .iter()
-        .filter(|f| matches!(f.vis, Visibility::Public(_)))
-        .collect::<Vec<_>>();
-
-    let non_pub_fields: Vec<_> = fields
-        .iter()
-        .filter(|f| !matches!(f.vis, Visibility::Public(_)))
-        .collect::<Vec<_>>();
-
-    let pub_field_names = pub_fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
-    let pub_field_types = pub_fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
-
-    let non_pub_field_names = non_pub_fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
-
-    let expanded = quote! {
-        impl #impl_generics #name #ty_generics #where_clause {
-            #[must_use]
-            pub fn new(#(#pub_field_names: #pub_field_types),*) -> Self {
-                Self {
-                    #(#pub_field_names),*,
-                    #(#non_pub_field_names: Default::default()),*
-                }
-            }
-        }
-    };
-
-    TokenStream::from(expanded)
+    expand::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
@@ -0,0 +1,73 @@
+use crate::case;
+use crate::expand::Param;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote};
+
+/// Generates a `macro_rules!` named after the struct in snake_case that accepts `field: value`
+/// pairs in any order and forwards them to the positional constructor.
+///
+/// The macro is a token muncher: every field owns one `[...]` slot that starts empty and is filled
+/// when its `field: value` pair is consumed. Once the input is exhausted and every slot holds an
+/// expression, the slots are passed to the constructor in parameter order. Missing, duplicate or
+/// unknown fields leave no rule matching.
+pub(crate) fn named_args_macro(name: &Ident, ctor: &TokenStream, params: &[Param]) -> TokenStream {
+    let macro_name = Ident::new(&case::to_snake_case(&name.to_string()), name.span());
+    let keys = params.iter().flat_map(Param::keys).collect::<Vec<_>>();
+    let slots = (0..keys.len())
+        .map(|i| format_ident!("__slot{}", i))
+        .collect::<Vec<_>>();
+    let internal = Ident::new("__impl_new", Span::call_site());
+
+    let munchers = keys.iter().enumerate().map(|(i, ident)| {
+        let pattern = slots.iter().enumerate().map(|(j, slot)| {
+            if i == j {
+                quote! { [] }
+            } else {
+                quote! { [$($#slot:tt)?] }
+            }
+        });
+        let forward = slots.iter().enumerate().map(|(j, slot)| {
+            if i == j {
+                quote! { [$__value] }
+            } else {
+                quote! { [$($#slot)?] }
+            }
+        });
+        quote! {
+            (@#internal #(#pattern)* #ident : $__value:expr $(, $($__rest:tt)*)?) => {
+                #macro_name!(@#internal #(#forward)* $($($__rest)*)?)
+            };
+        }
+    });
+
+    let mut slot_iter = slots.iter();
+    let args = params
+        .iter()
+        .map(|param| {
+            let members = param
+                .keys()
+                .iter()
+                .map(|_| slot_iter.next().unwrap())
+                .map(|slot| quote! { $#slot })
+                .collect::<Vec<_>>();
+            match param {
+                Param::Single(_) => quote! { #(#members)* },
+                Param::Group { .. } => quote! { (#(#members,)*) },
+            }
+        })
+        .collect::<Vec<_>>();
+    let empty = slots.iter().map(|_| quote! { [] });
+
+    quote! {
+        #[allow(unused_macros)]
+        macro_rules! #macro_name {
+            #(#munchers)*
+            (@#internal #([$#slots:expr])*) => {
+                #ctor(#(#args),*)
+            };
+            ($($__args:tt)*) => {
+                #macro_name!(@#internal #(#empty)* $($__args)*)
+            };
+        }
+    }
+}
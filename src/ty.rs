@@ -0,0 +1,201 @@
+use proc_macro2::{Group, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::parse::Parse;
+use syn::{GenericArgument, Generics, PathArguments, Type};
+
+/// Returns `true` if the last path segment of `ty` is `name`, e.g. `PhantomData` for both
+/// `PhantomData<T>` and `std::marker::PhantomData<T>`.
+fn is_named(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == name),
+        _ => false,
+    }
+}
+
+/// Replaces every `Self` in `node`, such as a type or a where-clause, with `self_ty`.
+pub(crate) fn replace_self<T: Parse + ToTokens + Clone>(node: &T, self_ty: &TokenStream) -> T {
+    syn::parse2(replace_self_tokens(node.to_token_stream(), self_ty))
+        .unwrap_or_else(|_| node.clone())
+}
+
+fn replace_self_tokens(tokens: TokenStream, self_ty: &TokenStream) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|tree| match tree {
+            TokenTree::Ident(ident) if ident == "Self" => self_ty.clone(),
+            TokenTree::Group(group) => {
+                let mut replaced = Group::new(
+                    group.delimiter(),
+                    replace_self_tokens(group.stream(), self_ty),
+                );
+                replaced.set_span(group.span());
+                TokenTree::Group(replaced).into_token_stream()
+            }
+            tree => tree.into_token_stream(),
+        })
+        .collect()
+}
+
+/// Returns `true` for `PhantomData` fields, which are never constructor parameters.
+pub(crate) fn is_phantom_data(ty: &Type) -> bool {
+    is_named(ty, "PhantomData")
+}
+
+/// Returns `true` for `PhantomPinned` fields, which are never constructor parameters either.
+pub(crate) fn is_phantom_pinned(ty: &Type) -> bool {
+    is_named(ty, "PhantomPinned")
+}
+
+/// Returns `true` for `OnceCell<T>` and `OnceLock<T>` fields, which start out empty through their
+/// own `new()`.
+pub(crate) fn is_once_cell(ty: &Type) -> bool {
+    ["OnceCell", "OnceLock"]
+        .iter()
+        .any(|name| is_named(ty, name))
+        && type_args(ty).len() == 1
+}
+
+/// Returns `true` for `String` fields, which `#[new(str_params)]` takes as `&str`.
+pub(crate) fn is_string(ty: &Type) -> bool {
+    is_named(ty, "String") && type_args(ty).is_empty()
+}
+
+/// Returns `true` for `Duration` fields, such as `std::time::Duration` or `core::time::Duration`.
+pub(crate) fn is_duration(ty: &Type) -> bool {
+    is_named(ty, "Duration") && type_args(ty).is_empty()
+}
+
+/// Returns `true` for the string types a `&'static str` converts into: `&str`, `String`, and
+/// `Cow`, `Box`, `Rc` or `Arc` of `str`.
+pub(crate) fn is_string_like(ty: &Type) -> bool {
+    let is_str = |ty: &Type| matches!(ty, Type::Path(path) if path.path.is_ident("str"));
+    match ty {
+        Type::Reference(reference) => is_str(&reference.elem),
+        _ if is_string(ty) => true,
+        _ if ["Cow", "Box", "Rc", "Arc"]
+            .iter()
+            .any(|name| is_named(ty, name)) =>
+        {
+            type_args(ty).last().is_some_and(|arg| is_str(arg))
+        }
+        _ => false,
+    }
+}
+
+/// Returns `true` if `ty` is one of the type parameters of `generics`, written bare as `T`.
+pub(crate) fn is_type_param(ty: &Type, generics: &Generics) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => generics
+            .type_params()
+            .any(|param| path.path.is_ident(&param.ident)),
+        _ => false,
+    }
+}
+
+/// Returns the angle-bracketed type arguments of the last segment of a path type.
+fn type_args(ty: &Type) -> Vec<&Type> {
+    let Type::Path(path) = ty else {
+        return Vec::new();
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return Vec::new();
+    };
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return Vec::new();
+    };
+    args.args
+        .iter()
+        .filter_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Item type a collection field is built from.
+///
+/// Single-parameter collections such as `Vec<T>` yield `T` and maps such as `HashMap<K, V>` yield
+/// `(K, V)` directly so the generated signature stays readable; anything else falls back to the
+/// field's own `IntoIterator::Item`.
+pub(crate) fn iter_item(ty: &Type) -> TokenStream {
+    let is_map = ["HashMap", "BTreeMap", "IndexMap"]
+        .iter()
+        .any(|map| is_named(ty, map));
+    match type_args(ty).as_slice() {
+        [item] => quote! { #item },
+        [key, value, ..] if is_map => quote! { (#key, #value) },
+        _ => quote! { <#ty as ::core::iter::IntoIterator>::Item },
+    }
+}
+
+/// Value type wrapped by a field whose type is one of the single-parameter `wrappers`, such as
+/// `Mutex<T>` or `RefCell<T>`.
+pub(crate) fn wrapped_inner<'a>(ty: &'a Type, wrappers: &[&str]) -> Option<&'a Type> {
+    if !wrappers.iter().any(|wrapper| is_named(ty, wrapper)) {
+        return None;
+    }
+    match type_args(ty).as_slice() {
+        [inner] => Some(inner),
+        _ => None,
+    }
+}
+
+/// Raw integer type of a `NonZeroU32`-style or `NonZero<T>` field, if the field is one.
+pub(crate) fn nonzero_inner(ty: &Type) -> Option<TokenStream> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let name = segment.ident.to_string();
+    if name == "NonZero" {
+        return match type_args(ty).as_slice() {
+            [inner] => Some(quote! { #inner }),
+            _ => None,
+        };
+    }
+    let raw = name.strip_prefix("NonZero")?;
+    matches!(
+        raw,
+        "U8" | "U16"
+            | "U32"
+            | "U64"
+            | "U128"
+            | "Usize"
+            | "I8"
+            | "I16"
+            | "I32"
+            | "I64"
+            | "I128"
+            | "Isize"
+    )
+    .then(|| {
+        let raw = proc_macro2::Ident::new(&raw.to_lowercase(), segment.ident.span());
+        quote! { #raw }
+    })
+}
+
+/// Explains why an obviously unsized type cannot be taken as a by-value parameter, suggesting
+/// sized alternatives.
+pub(crate) fn unsized_hint(ty: &Type) -> Option<&'static str> {
+    match ty {
+        Type::Paren(paren) => unsized_hint(&paren.elem),
+        Type::Group(group) => unsized_hint(&group.elem),
+        Type::Slice(_) => Some(
+            "slices are unsized and cannot be passed by value; \
+             use `&[T]`, `Box<[T]>` or `Vec<T>` for this field",
+        ),
+        Type::TraitObject(_) => Some(
+            "trait objects are unsized and cannot be passed by value; \
+             use `&dyn Trait` or `Box<dyn Trait>` for this field",
+        ),
+        Type::Path(path) if path.qself.is_none() && path.path.is_ident("str") => Some(
+            "`str` is unsized and cannot be passed by value; \
+             use `&str`, `Box<str>` or `String` for this field",
+        ),
+        _ => None,
+    }
+}
@@ -0,0 +1,249 @@
+use crate::expand::Param;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{GenericParam, Generics, Visibility};
+
+/// Generates a type-state builder for the constructor, named `<Struct>Builder`.
+///
+/// The builder has one type parameter per constructor argument, in addition to the struct's own
+/// generics. It is `()` while the argument is unset and `(T,)` once its setter has been called,
+/// and each setter is only implemented while its parameter is `()`. `build` is only implemented
+/// once every parameter is a `(T,)`, so forgetting a required field fails to compile. `build`
+/// forwards the arguments to `ctor`, so the builder returns exactly what the constructor does.
+pub(crate) struct TypedBuilder<'a> {
+    pub name: &'a Ident,
+    /// Prepended to the names of the generated types, from `#[new(prefix = "...")]`.
+    pub prefix: &'a str,
+    /// Name of the entry point, `builder` unless set with `#[new(expose_builder_fn = "...")]`.
+    pub entry_fn: &'a Ident,
+    pub struct_vis: &'a Visibility,
+    pub vis: &'a TokenStream,
+    pub generics: &'a Generics,
+    pub target: &'a TokenStream,
+    pub ctor: &'a TokenStream,
+    pub output: &'a TokenStream,
+    /// `#[must_use]` for `build`, empty when the constructor's is, such as for a `Result`, which
+    /// is already `#[must_use]`.
+    pub must_use: &'a TokenStream,
+    /// Whether the constructor is an `async fn`, which makes `build` one too.
+    pub is_async: bool,
+    pub params: &'a [Param<'a>],
+}
+
+impl TypedBuilder<'_> {
+    /// The builder's name, e.g. `ServerBuilder`, or `InternalServerBuilder` with a prefix.
+    pub fn ident(&self) -> Ident {
+        format_ident!("{}{}Builder", self.prefix, self.name)
+    }
+
+    /// The `builder()` entry point, or the one named by `entry_fn`, placed in the struct's
+    /// inherent impl.
+    pub fn entry(&self) -> TokenStream {
+        let builder = self.ident();
+        let entry_fn = self.entry_fn;
+        let vis = self.vis;
+        let args = generic_args(self.generics);
+        let unset = self
+            .params
+            .iter()
+            .flat_map(Param::names)
+            .map(|_| quote! { () });
+        let empty = self
+            .params
+            .iter()
+            .flat_map(Param::names)
+            .map(|name| quote! { #name: () });
+        let doc = format!(
+            " Starts a [`{builder}`], whose `build` is available once every parameter is set."
+        );
+        quote! {
+            #[doc = #doc]
+            #[must_use]
+            #vis fn #entry_fn() -> #builder<#(#args,)* #(#unset),*> {
+                #builder {
+                    #(#empty,)*
+                    __marker: ::core::marker::PhantomData,
+                }
+            }
+        }
+    }
+
+    /// The builder struct and its setter and `build` impls.
+    pub fn items(&self) -> TokenStream {
+        let builder = self.ident();
+        let struct_vis = self.struct_vis;
+        let vis = self.vis;
+        let target = self.target;
+        let names = self
+            .params
+            .iter()
+            .flat_map(Param::names)
+            .collect::<Vec<_>>();
+        let tys = self.params.iter().flat_map(Param::tys).collect::<Vec<_>>();
+        let states = (0..names.len())
+            .map(|i| format_ident!("__F{}", i))
+            .collect::<Vec<_>>();
+        let args = generic_args(self.generics);
+        let where_clause = &self.generics.where_clause;
+
+        let decl_params = declared_params(self.generics);
+        let impl_params = impl_params(self.generics);
+
+        let doc = format!(
+            " Builder for [`{}`], returned by `{}::{}()`.",
+            self.name, self.name, self.entry_fn
+        );
+        let declaration = quote! {
+            #[doc = #doc]
+            #[must_use]
+            #struct_vis struct #builder<#(#decl_params,)* #(#states),*> #where_clause {
+                #(#names: #states,)*
+                __marker: ::core::marker::PhantomData<fn() -> #target>,
+            }
+        };
+
+        let setters = names.iter().zip(&tys).enumerate().map(|(i, (name, ty))| {
+            let others = states
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, state)| state);
+            let before = states.iter().enumerate().map(|(j, state)| {
+                if i == j {
+                    quote! { () }
+                } else {
+                    quote! { #state }
+                }
+            });
+            let after = states.iter().enumerate().map(|(j, state)| {
+                if i == j {
+                    quote! { (#ty,) }
+                } else {
+                    quote! { #state }
+                }
+            });
+            let moves = names.iter().enumerate().map(|(j, other)| {
+                if i == j {
+                    quote! { #other: (#name,) }
+                } else {
+                    quote! { #other: self.#other }
+                }
+            });
+            quote! {
+                #[automatically_derived]
+                impl<#(#impl_params,)* #(#others),*> #builder<#(#args,)* #(#before),*> #where_clause {
+                    #[allow(non_snake_case)]
+                    #vis fn #name(self, #name: #ty) -> #builder<#(#args,)* #(#after),*> {
+                        #builder {
+                            #(#moves,)*
+                            __marker: ::core::marker::PhantomData,
+                        }
+                    }
+                }
+            }
+        });
+
+        let mut fields = names.iter();
+        let call_args = self.params.iter().map(|param| {
+            let members = param
+                .names()
+                .iter()
+                .map(|_| fields.next().expect("builder field"))
+                .map(|field| quote! { self.#field.0 })
+                .collect::<Vec<_>>();
+            match param {
+                Param::Single(_) => quote! { #(#members)* },
+                Param::Group { .. } => quote! { (#(#members,)*) },
+            }
+        });
+        let ctor = self.ctor;
+        let output = self.output;
+        let must_use = self.must_use;
+        let (asyncness, await_ctor) = if self.is_async {
+            (quote! { async }, quote! { .await })
+        } else {
+            (quote! {}, quote! {})
+        };
+        let build = quote! {
+            #[automatically_derived]
+            impl<#(#impl_params),*> #builder<#(#args,)* #((#tys,)),*> #where_clause {
+                /// Builds the value from the parameters set on the builder.
+                #must_use
+                #vis #asyncness fn build(self) -> #output {
+                    #ctor(#(#call_args),*) #await_ctor
+                }
+            }
+        };
+
+        quote! {
+            #declaration
+            #(#setters)*
+            #build
+        }
+    }
+}
+
+/// The struct's generic parameters as arguments, e.g. `'a, T, N` for `<'a, T: Clone, const N: usize>`.
+pub(crate) fn generic_args(generics: &Generics) -> Vec<TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(def) => {
+                let lifetime = &def.lifetime;
+                quote! { #lifetime }
+            }
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote! { #ident }
+            }
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote! { #ident }
+            }
+        })
+        .collect()
+}
+
+/// The struct's generic parameters as declared on a new item, e.g. `T: Clone` for `T: Clone = u8`,
+/// since defaults are only allowed on the struct itself.
+pub(crate) fn declared_params(generics: &Generics) -> Vec<TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Type(param) => {
+                let mut param = param.clone();
+                param.eq_token = None;
+                param.default = None;
+                quote! { #param }
+            }
+            GenericParam::Const(param) => {
+                let mut param = param.clone();
+                param.eq_token = None;
+                param.default = None;
+                quote! { #param }
+            }
+            GenericParam::Lifetime(param) => quote! { #param },
+        })
+        .collect()
+}
+
+/// The struct's generic parameters with their bounds, for an `impl<...>` of a derived item.
+pub(crate) fn impl_params(generics: &Generics) -> Vec<TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Type(param) => {
+                let (ident, bounds) = (&param.ident, &param.bounds);
+                quote! { #ident: #bounds }
+            }
+            GenericParam::Const(param) => {
+                let (ident, ty) = (&param.ident, &param.ty);
+                quote! { const #ident: #ty }
+            }
+            GenericParam::Lifetime(param) => quote! { #param },
+        })
+        .collect()
+}
@@ -0,0 +1,41 @@
+//! Allocator-generic collection fields, which need the unstable `allocator_api`. Run with
+//! `RUSTFLAGS="--cfg nightly" cargo +nightly test --test allocator`.
+
+#![cfg(nightly)]
+#![feature(allocator_api)]
+
+use impl_new_derive::ImplNew;
+use std::alloc::{Allocator, Global};
+
+#[derive(ImplNew)]
+struct Buffer<T, A: Allocator = Global> {
+    pub items: Vec<T, A>,
+    reads: usize,
+}
+
+#[derive(ImplNew)]
+#[new(resource(alloc: A))]
+struct Pool<T, A: Allocator + Clone> {
+    pub name: &'static str,
+    #[new(default(Vec::new_in(alloc.clone())))]
+    free: Vec<T, A>,
+    #[new(default(Vec::new_in(alloc)))]
+    used: Vec<T, A>,
+}
+
+#[test]
+fn allocator_generics_are_threaded_through_the_impl() {
+    let mut buffer = Buffer::new(Vec::new_in(Global));
+    buffer.items.push(1u8);
+    assert_eq!((buffer.items.len(), buffer.reads), (1, 0));
+
+    let default_alloc: Buffer<u8> = Buffer::new(Vec::new());
+    assert!(default_alloc.items.is_empty());
+}
+
+#[test]
+fn allocators_can_be_passed_as_resources_for_private_collections() {
+    let mut pool = Pool::<u32, Global>::new(Global, "ids");
+    pool.free.push(7);
+    assert_eq!((pool.name, pool.free.len(), pool.used.len()), ("ids", 1, 0));
+}
@@ -0,0 +1,54 @@
+use impl_new_derive::ImplNew;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+/// Polls `future` to completion; the futures below never wait on anything.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+async fn fetch_limit() -> u32 {
+    128
+}
+
+async fn parse_port(raw: &str) -> Result<u16, std::num::ParseIntError> {
+    raw.parse()
+}
+
+#[derive(ImplNew)]
+#[new(async)]
+struct Connection {
+    pub url: &'static str,
+    #[new(default(fetch_limit().await))]
+    limit: u32,
+}
+
+#[derive(ImplNew)]
+#[new(async, try_new, typed_builder)]
+struct Listener {
+    pub host: &'static str,
+    #[new(default(parse_port("8080").await?))]
+    port: u16,
+}
+
+#[test]
+fn async_new_awaits_its_defaults() {
+    let connection = block_on(Connection::new("db://local"));
+    assert_eq!((connection.url, connection.limit), ("db://local", 128));
+}
+
+#[test]
+fn async_try_new_and_builder_await_the_constructor() {
+    let listener = block_on(Listener::try_new("0.0.0.0")).unwrap();
+    assert_eq!((listener.host, listener.port), ("0.0.0.0", 8080));
+
+    let built = block_on(Listener::builder().host("::").build()).unwrap();
+    assert_eq!(built.port, 8080);
+}
@@ -0,0 +1,118 @@
+use impl_new_derive::ImplNew;
+
+#[derive(Debug, PartialEq, ImplNew)]
+#[new(checked_builder)]
+pub struct Server {
+    pub host: String,
+    pub port: u16,
+    #[new(default(30))]
+    timeout: u64,
+}
+
+#[test]
+fn build_returns_the_value_once_every_parameter_is_set() {
+    let server = Server::builder()
+        .port(8080)
+        .host("localhost".to_string())
+        .build();
+    assert_eq!(server, Ok(Server::new("localhost".to_string(), 8080)));
+    assert_eq!(server.unwrap().timeout, 30);
+}
+
+#[test]
+fn build_reports_the_first_unset_parameter() {
+    let missing_host = Server::builder().port(80).build();
+    assert_eq!(missing_host, Err(ServerBuilderError::MissingHost));
+    assert_eq!(
+        Server::builder().build(),
+        Err(ServerBuilderError::MissingHost)
+    );
+
+    let error = Server::builder()
+        .host("localhost".to_string())
+        .build()
+        .unwrap_err();
+    assert_eq!(error, ServerBuilderError::MissingPort);
+    assert_eq!(error.to_string(), "`port` was not set");
+    let _: &dyn std::error::Error = &error;
+}
+
+#[test]
+fn builders_can_be_filled_in_conditionally() {
+    let mut builder = Server::builder().host("example.org".to_string());
+    if cfg!(test) {
+        builder = builder.port(443);
+    }
+    assert_eq!(builder.build().unwrap().port, 443);
+}
+
+#[derive(ImplNew)]
+#[new(checked_builder)]
+struct Slot<'a, T: Clone, const N: usize> {
+    pub name: &'a str,
+    pub items: [T; N],
+    #[new(group = "pos")]
+    pub x: i32,
+    #[new(group = "pos")]
+    pub y: i32,
+}
+
+#[test]
+fn builder_keeps_generics_and_groups() {
+    let slot = Slot::builder()
+        .y(2)
+        .items(['a'; 2])
+        .x(1)
+        .name("slot")
+        .build()
+        .unwrap();
+    assert_eq!(
+        (slot.name, slot.items, slot.x, slot.y),
+        ("slot", ['a'; 2], 1, 2)
+    );
+
+    let missing = Slot::<char, 1>::builder().name("slot").x(1).build();
+    assert!(matches!(missing, Err(SlotBuilderError::MissingItems)));
+}
+
+#[derive(ImplNew)]
+#[new(checked_builder)]
+struct Marker {
+    hits: u32,
+}
+
+#[test]
+fn builders_without_parameters_always_build() {
+    assert_eq!(Marker::builder().build().map(|marker| marker.hits), Ok(0));
+}
+
+#[derive(Debug, ImplNew)]
+#[new(checked_builder, prefix = "Raw")]
+struct Packet {
+    pub id: u16,
+}
+
+#[test]
+fn prefix_renames_the_generated_types() {
+    let builder: RawPacketBuilder = Packet::builder();
+    let packet = builder.id(1).build().unwrap();
+    assert_eq!(packet.id, 1);
+    let error: RawPacketBuilderError = Packet::builder().build().unwrap_err();
+    assert_eq!(error, RawPacketBuilderError::MissingId);
+}
+
+#[derive(Debug, ImplNew)]
+#[new(checked_builder, expose_builder_fn = "configure")]
+struct Upload {
+    pub path: &'static str,
+}
+
+#[test]
+fn expose_builder_fn_names_the_entry_point() {
+    let upload = Upload::configure().path("a.bin").build().unwrap();
+    assert_eq!(upload.path, "a.bin");
+    assert_eq!(
+        Upload::configure().build().unwrap_err(),
+        UploadBuilderError::MissingPath
+    );
+}
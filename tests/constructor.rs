@@ -0,0 +1,1019 @@
+use impl_new_derive::ImplNew;
+
+mod private_ctor {
+    use impl_new_derive::ImplNew;
+
+    #[derive(ImplNew)]
+    #[new(private)]
+    pub struct Token {
+        pub value: u32,
+        nonce: u64,
+    }
+
+    impl Token {
+        pub fn issue(value: u32) -> Self {
+            Self::new(value)
+        }
+
+        pub fn nonce(&self) -> u64 {
+            self.nonce
+        }
+    }
+}
+
+#[test]
+fn private_constructor_is_usable_inside_its_module() {
+    let token = private_ctor::Token::issue(7);
+    assert_eq!(token.value, 7);
+    assert_eq!(token.nonce(), 0);
+}
+
+#[derive(ImplNew)]
+struct Public {
+    pub value: u32,
+    hidden: u32,
+}
+
+#[test]
+fn default_constructor_is_public() {
+    let public = Public::new(3);
+    assert_eq!(public.value, 3);
+    assert_eq!(public.hidden, 0);
+}
+
+#[derive(ImplNew)]
+#[new(free_fn = "new_account")]
+struct Account<T> {
+    pub owner: String,
+    pub balance: T,
+    history: Vec<T>,
+}
+
+#[test]
+fn free_fn_constructs_outside_an_impl() {
+    let account = new_account("ada".to_string(), 10u64);
+    assert_eq!(account.owner, "ada");
+    assert_eq!(account.balance, 10);
+    assert!(account.history.is_empty());
+}
+
+#[derive(ImplNew)]
+#[new(hot)]
+struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(ImplNew)]
+#[new(inline = "never", no_must_use)]
+struct Cold {
+    pub id: u8,
+}
+
+#[test]
+#[deny(unused_must_use)]
+fn hot_constructor_is_not_must_use() {
+    Vec2::new(1.0, 2.0);
+    Cold::new(1);
+    let v = Vec2::new(3.0, 4.0);
+    assert_eq!((v.x, v.y), (3.0, 4.0));
+    assert_eq!(Cold::new(9).id, 9);
+}
+
+#[derive(ImplNew)]
+#[new(doc_defaults)]
+struct Documented {
+    pub name: String,
+    cache: Vec<u8>,
+    #[new(default(30))]
+    timeout: u64,
+}
+
+#[test]
+fn doc_defaults_keeps_the_constructor_intact() {
+    let documented = Documented::new("docs".to_string());
+    assert_eq!(documented.name, "docs");
+    assert!(documented.cache.is_empty());
+    assert_eq!(documented.timeout, 30);
+}
+
+#[derive(ImplNew)]
+#[new(doc_args)]
+struct DocumentedArgs {
+    pub name: String,
+    #[new(from_iter)]
+    pub tags: Vec<&'static str>,
+    cache: Vec<u8>,
+}
+
+#[test]
+fn doc_args_keeps_the_constructor_intact() {
+    let documented = DocumentedArgs::new("docs".to_string(), ["a"]);
+    assert_eq!(
+        (documented.name.as_str(), documented.tags),
+        ("docs", vec!["a"])
+    );
+    assert!(documented.cache.is_empty());
+}
+
+#[derive(ImplNew)]
+#[new(metadata)]
+struct Described {
+    pub name: String,
+    pub r#type: u8,
+    secret: u64,
+}
+
+#[test]
+fn metadata_lists_all_fields() {
+    assert_eq!(Described::FIELD_COUNT, 3);
+    assert_eq!(Described::FIELD_NAMES, ["name", "type", "secret"]);
+
+    let described = Described::new("meta".to_string(), 1);
+    assert_eq!((described.name.as_str(), described.r#type), ("meta", 1));
+    assert_eq!(described.secret, 0);
+}
+
+#[derive(ImplNew)]
+#[new(macro)]
+struct HttpRequest {
+    pub method: &'static str,
+    #[new(group = "target")]
+    pub host: &'static str,
+    #[new(group = "target")]
+    pub port: u16,
+    pub body: Vec<u8>,
+    retries: u8,
+}
+
+#[test]
+fn named_args_macro_reorders_arguments() {
+    let request = http_request! {
+        body: vec![1, 2],
+        port: 8080,
+        method: "GET",
+        host: "localhost",
+    };
+    assert_eq!(request.method, "GET");
+    assert_eq!((request.host, request.port), ("localhost", 8080));
+    assert_eq!(request.body, [1, 2]);
+    assert_eq!(request.retries, 0);
+
+    let in_order = http_request!(method: "PUT", host: "h", port: 1, body: Vec::new());
+    assert_eq!(in_order.method, "PUT");
+}
+
+macro_rules! mangled {
+    ($vis:vis struct $name:ident { $($field:tt)* }) => {
+        #[derive(ImplNew)]
+        #[new(self_ty = "Widget", macro)]
+        $vis struct $name { $($field)* }
+    };
+}
+
+mangled!(
+    struct __WidgetImpl0 {
+        pub id: u32,
+        version: u8,
+    }
+);
+
+type Widget = __WidgetImpl0;
+
+#[test]
+fn self_ty_writes_the_impl_for_an_alias() {
+    let widget: Widget = Widget::new(5);
+    assert_eq!(widget.id, 5);
+    assert_eq!(widget.version, 0);
+}
+
+#[test]
+fn named_args_macro_follows_self_ty() {
+    let from_macro = widget!(id: 6);
+    assert_eq!(from_macro.id, 6);
+}
+
+#[derive(ImplNew)]
+#[new(rename_all = "camelCase")]
+struct Binding {
+    pub request_timeout: u64,
+    #[new(group = "size")]
+    pub max_width: u32,
+    #[new(group = "size")]
+    pub max_height: u32,
+    pub r#type: &'static str,
+}
+
+#[test]
+fn rename_all_changes_parameter_names_only() {
+    let binding = Binding::new(30, (640, 480), "json");
+    assert_eq!(binding.request_timeout, 30);
+    assert_eq!((binding.max_width, binding.max_height), (640, 480));
+    assert_eq!(binding.r#type, "json");
+}
+
+#[derive(ImplNew)]
+#[new(post = "value.register()")]
+struct Listener {
+    pub port: u16,
+    registered: bool,
+    id: u32,
+}
+
+impl Listener {
+    fn register(&mut self) {
+        self.registered = true;
+        self.id = u32::from(self.port) * 10;
+    }
+}
+
+#[test]
+fn post_hook_runs_on_the_constructed_value() {
+    let listener = Listener::new(8);
+    assert_eq!(listener.port, 8);
+    assert!(listener.registered);
+    assert_eq!(listener.id, 80);
+}
+
+#[derive(ImplNew)]
+#[new(pinned)]
+struct Task<F> {
+    pub future: F,
+    polls: u32,
+}
+
+#[test]
+fn pinned_returns_a_pinned_box() {
+    let task: std::pin::Pin<Box<Task<std::future::Ready<u8>>>> = Task::new(std::future::ready(3));
+    assert_eq!(task.polls, 0);
+    let _ = &task.future;
+}
+
+#[derive(ImplNew)]
+pub struct Registry {
+    entries: Vec<&'static str>,
+    #[new(default(16))]
+    capacity: usize,
+}
+
+#[test]
+fn zero_parameter_new_allows_new_without_default() {
+    let registry = Registry::new();
+    assert!(registry.entries.is_empty());
+    assert_eq!(registry.capacity, 16);
+}
+
+#[derive(Debug, PartialEq, ImplNew)]
+#[new(impl_default)]
+pub struct Counters {
+    #[new(default(1))]
+    step: u32,
+    total: u64,
+}
+
+#[test]
+fn impl_default_calls_the_constructor() {
+    assert_eq!(Counters::default(), Counters::new());
+    assert_eq!(Counters::default().step, 1);
+    assert_eq!(Counters::default().total, 0);
+}
+
+#[derive(Debug, Default, PartialEq, ImplNew)]
+#[new(default)]
+struct Settings {
+    pub verbose: bool,
+    pub level: u8,
+}
+
+#[derive(Debug, Default, PartialEq, ImplNew)]
+#[new(default)]
+struct Stack<T> {
+    pub items: Vec<T>,
+}
+
+#[test]
+fn struct_level_default_aliases_default() {
+    assert_eq!(Settings::new(), Settings::default());
+    assert!(!Settings::new().verbose);
+    assert_eq!(Settings::new().level, 0);
+    assert!(Stack::<String>::new().items.is_empty());
+}
+
+#[derive(ImplNew)]
+#[new(builder)]
+struct ServerConfig {
+    pub host: String,
+    #[new(default(8080))]
+    pub port: u16,
+    #[new(default)]
+    pub tags: Vec<&'static str>,
+    #[new(default(30))]
+    timeout: u64,
+}
+
+#[test]
+fn builder_adds_setters_for_defaulted_public_fields() {
+    let config = ServerConfig::new("localhost".to_string());
+    assert_eq!(config.port, 8080);
+    assert!(config.tags.is_empty());
+
+    let config = ServerConfig::new("example.org".to_string())
+        .with_port(443)
+        .with_tags(vec!["tls"]);
+    assert_eq!(config.host, "example.org");
+    assert_eq!(config.port, 443);
+    assert_eq!(config.tags, ["tls"]);
+    assert_eq!(config.timeout, 30);
+}
+
+#[derive(ImplNew)]
+#[new(builder, builder_into)]
+struct Mailer {
+    pub relay: String,
+    #[new(default("noreply".to_string()))]
+    pub sender: String,
+    #[new(default(25))]
+    pub port: u32,
+}
+
+#[test]
+fn builder_into_setters_convert_their_argument() {
+    let mailer = Mailer::new("smtp.local".to_string())
+        .with_sender("alerts")
+        .with_port(587u16);
+    assert_eq!(mailer.relay, "smtp.local");
+    assert_eq!(mailer.sender, "alerts");
+    assert_eq!(mailer.port, 587);
+}
+
+#[derive(ImplNew)]
+#[new(test_full)]
+struct Ledger {
+    pub owner: &'static str,
+    balance: i64,
+    #[new(default(true))]
+    active: bool,
+}
+
+#[test]
+fn test_full_takes_private_fields_in_tests() {
+    let ledger = Ledger::new_full("ada", -20, false);
+    assert_eq!(ledger.owner, "ada");
+    assert_eq!(ledger.balance, -20);
+    assert!(!ledger.active);
+
+    let regular = Ledger::new("bob");
+    assert_eq!(regular.balance, 0);
+    assert!(regular.active);
+}
+
+#[derive(Debug, PartialEq, ImplNew)]
+struct Empty {}
+
+#[derive(Debug, PartialEq, ImplNew)]
+struct OnlyMarkers<T, U> {
+    pub data: std::marker::PhantomData<T>,
+    marker: std::marker::PhantomData<fn() -> U>,
+}
+
+#[derive(Debug, PartialEq, ImplNew)]
+struct AllSkipped {
+    #[new(default)]
+    pub count: u32,
+    #[new(skip_if_default)]
+    pub name: String,
+}
+
+#[test]
+fn degenerate_field_sets_still_get_a_constructor() {
+    assert_eq!(Empty::new(), Empty {});
+    assert_eq!(
+        OnlyMarkers::<u8, String>::new(),
+        OnlyMarkers {
+            data: std::marker::PhantomData,
+            marker: std::marker::PhantomData,
+        }
+    );
+    let skipped = AllSkipped::new();
+    assert_eq!(skipped.count, 0);
+    assert!(skipped.name.is_empty());
+}
+
+#[derive(Debug, PartialEq, ImplNew)]
+struct SkipFirst {
+    #[new(skip_if_default)]
+    pub a: u8,
+    pub b: u8,
+    hidden: u8,
+    pub c: u8,
+}
+
+#[derive(Debug, PartialEq, ImplNew)]
+struct SkipMiddle {
+    pub a: u8,
+    #[new(default)]
+    pub b: u8,
+    pub c: u8,
+    hidden: u8,
+}
+
+#[derive(Debug, PartialEq, ImplNew)]
+struct SkipLast {
+    hidden: u8,
+    pub a: u8,
+    pub b: u8,
+    #[new(skip_if_default)]
+    pub c: u8,
+}
+
+#[test]
+fn skipping_any_public_field_leaves_the_others_in_order() {
+    let first = SkipFirst::new(2, 3);
+    assert_eq!(
+        first,
+        SkipFirst {
+            a: 0,
+            b: 2,
+            hidden: 0,
+            c: 3
+        }
+    );
+    let middle = SkipMiddle::new(1, 3);
+    assert_eq!(
+        middle,
+        SkipMiddle {
+            a: 1,
+            b: 0,
+            c: 3,
+            hidden: 0
+        }
+    );
+    let last = SkipLast::new(1, 2);
+    assert_eq!(
+        last,
+        SkipLast {
+            hidden: 0,
+            a: 1,
+            b: 2,
+            c: 0
+        }
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, ImplNew)]
+#[new(with_overrides)]
+struct Profile {
+    pub name: &'static str,
+    #[new(from_iter)]
+    pub roles: Vec<&'static str>,
+    #[new(default(3))]
+    pub retries: u8,
+    theme: &'static str,
+}
+
+#[test]
+fn with_overrides_keeps_the_remaining_fields_of_the_base() {
+    let mut base = Profile::new("base", ["reader"]);
+    base.retries = 9;
+    base.theme = "dark";
+
+    let derived = Profile::with(base.clone(), "admin", ["reader", "writer"]);
+    assert_eq!(derived.name, "admin");
+    assert_eq!(derived.roles, ["reader", "writer"]);
+    assert_eq!(derived.retries, 9);
+    assert_eq!(derived.theme, "dark");
+    assert_eq!(base.name, "base");
+}
+
+#[derive(ImplNew)]
+#[new(cfg = "test")]
+struct TestOnly {
+    pub value: u8,
+}
+
+#[derive(ImplNew)]
+#[new(cfg = "not(test)", macro)]
+struct NotInTests {
+    pub value: u8,
+}
+
+impl NotInTests {
+    // Would clash with the generated constructor if the `cfg` did not remove it.
+    fn new(value: u8) -> Self {
+        Self { value: value * 2 }
+    }
+}
+
+#[test]
+fn cfg_gates_the_generated_items() {
+    assert_eq!(TestOnly::new(1).value, 1);
+    assert_eq!(NotInTests::new(2).value, 4);
+}
+
+// The same struct, derived once per configuration, with defaults that differ per configuration.
+#[cfg(test)]
+#[derive(ImplNew)]
+struct Transport {
+    pub endpoint: &'static str,
+    #[cfg_attr(test, new(default("stub")))]
+    #[cfg_attr(not(test), new(default("tcp")))]
+    kind: &'static str,
+}
+
+#[cfg(not(test))]
+#[derive(ImplNew)]
+struct Transport {
+    pub endpoint: &'static str,
+    pub kind: &'static str,
+}
+
+#[derive(ImplNew)]
+struct Probe {
+    #[cfg_attr(test, new(default(Self::stub())))]
+    #[cfg_attr(not(test), new(default(Self::live())))]
+    source: &'static str,
+}
+
+impl Probe {
+    fn stub() -> &'static str {
+        "stub"
+    }
+
+    #[cfg(not(test))]
+    fn live() -> &'static str {
+        "live"
+    }
+}
+
+#[test]
+fn feature_conditional_defaults_select_the_constructor_per_configuration() {
+    let transport = Transport::new("localhost");
+    assert_eq!((transport.endpoint, transport.kind), ("localhost", "stub"));
+    assert_eq!(Probe::new().source, "stub");
+}
+
+// `#[cfg]` fields are removed before the derive runs, so each platform's `new` takes exactly the
+// fields that exist there.
+#[derive(ImplNew)]
+struct Process {
+    pub pid: u32,
+    #[cfg(unix)]
+    pub uid: u32,
+    #[cfg(windows)]
+    pub session: String,
+    #[cfg(unix)]
+    groups: Vec<u32>,
+}
+
+#[cfg(unix)]
+#[test]
+fn cfg_fields_present_on_unix_are_parameters() {
+    let process = Process::new(1, 1000);
+    assert_eq!((process.pid, process.uid), (1, 1000));
+    assert!(process.groups.is_empty());
+}
+
+#[cfg(windows)]
+#[test]
+fn cfg_fields_present_on_windows_are_parameters() {
+    let process = Process::new(1, "console".to_string());
+    assert_eq!((process.pid, process.session.as_str()), (1, "console"));
+}
+
+// A `cfg_if!`-style definition: one struct per platform, each deriving its own `new`.
+macro_rules! per_platform {
+    ($(#[cfg($meta:meta)] $item:item)*) => {
+        $(#[cfg($meta)] $item)*
+    };
+}
+
+per_platform! {
+    #[cfg(unix)]
+    #[derive(ImplNew)]
+    struct Socket {
+        pub path: &'static str,
+    }
+
+    #[cfg(not(unix))]
+    #[derive(ImplNew)]
+    struct Socket {
+        pub port: u16,
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn conditionally_defined_structs_derive_per_platform() {
+    assert_eq!(Socket::new("/tmp/app.sock").path, "/tmp/app.sock");
+}
+
+#[cfg(not(unix))]
+#[test]
+fn conditionally_defined_structs_derive_per_platform() {
+    assert_eq!(Socket::new(8080).port, 8080);
+}
+
+macro_rules! define_struct {
+    ($name:ident, $field:ident: $ty:ty) => {
+        #[derive(ImplNew)]
+        #[new(macro, rename_all = "camelCase")]
+        struct $name {
+            pub $field: $ty,
+            pub fixed_name: &'static str,
+            #[new(init)]
+            hidden: u8,
+        }
+
+        impl $name {
+            fn hidden(&self) -> u8 {
+                self.hidden
+            }
+        }
+    };
+}
+
+define_struct!(Generated, generated_value: u32);
+
+#[test]
+fn structs_emitted_by_macro_rules_keep_their_field_idents() {
+    let generated = Generated::new(7, "fixed", || 3);
+    assert_eq!(generated.generated_value, 7);
+    assert_eq!(generated.fixed_name, "fixed");
+    assert_eq!(generated.hidden(), 3);
+
+    let generated = generated! { fixed_name: "named", hidden: || 1, generated_value: 2 };
+    assert_eq!((generated.generated_value, generated.hidden()), (2, 1));
+}
+
+mod registry {
+    use std::sync::{Arc, Mutex};
+
+    pub static LIVE: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+    pub fn track(plugin: super::Plugin) -> Arc<super::Plugin> {
+        LIVE.lock().unwrap().push(plugin.name);
+        Arc::new(plugin)
+    }
+}
+
+#[derive(ImplNew)]
+#[new(wrap = "registry::track", returns = "std::sync::Arc<Self>")]
+struct Plugin {
+    pub name: &'static str,
+}
+
+#[test]
+fn wrap_passes_the_value_through_a_function() {
+    let plugin: std::sync::Arc<Plugin> = Plugin::new("audio");
+    assert_eq!(plugin.name, "audio");
+    assert_eq!(*registry::LIVE.lock().unwrap(), ["audio"]);
+
+    let shared = std::sync::Arc::clone(&plugin);
+    assert_eq!(std::sync::Arc::strong_count(&shared), 2);
+}
+
+#[derive(ImplNew)]
+#[new(params(id, secret))]
+struct Tenant {
+    pub id: u32,
+    secret: String,
+    pub region: String,
+    pub quota: u64,
+    #[new(default(8))]
+    workers: u8,
+}
+
+#[test]
+fn params_lists_the_only_parameters() {
+    let tenant = Tenant::new(5, "s3cr3t".to_string());
+    assert_eq!((tenant.id, tenant.secret.as_str()), (5, "s3cr3t"));
+    assert_eq!((tenant.region.as_str(), tenant.quota), ("", 0));
+    assert_eq!(tenant.workers, 8);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ImplNew)]
+#[new(copy_ctor)]
+struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    alpha: u8,
+}
+
+#[test]
+fn copy_structs_compose_with_the_derive() {
+    let color = Rgb::new(1, 2, 3);
+    let copied = color;
+    assert_eq!(color, copied);
+    assert_eq!(Rgb::from_ref(&color), color);
+    assert_eq!(color.alpha, 0);
+}
+
+mod outer {
+    pub mod inner {
+        use impl_new_derive::ImplNew;
+
+        #[derive(ImplNew)]
+        pub struct Nested {
+            pub depth: u8,
+            label: &'static str,
+        }
+
+        impl Nested {
+            pub fn label(&self) -> &'static str {
+                self.label
+            }
+        }
+    }
+
+    pub use inner::Nested as Reexported;
+}
+
+#[test]
+fn structs_in_nested_modules_get_their_impl() {
+    let nested = outer::inner::Nested::new(2);
+    assert_eq!((nested.depth, nested.label()), (2, ""));
+    assert_eq!(outer::Reexported::new(3).depth, 3);
+}
+
+#[test]
+fn structs_inside_functions_get_their_impl() {
+    #[derive(ImplNew)]
+    struct Local {
+        pub value: i32,
+    }
+
+    fn helper() -> i32 {
+        #[derive(ImplNew)]
+        struct Local {
+            pub value: i32,
+            #[new(default(10))]
+            offset: i32,
+        }
+
+        let local = Local::new(1);
+        local.value + local.offset
+    }
+
+    assert_eq!(Local::new(5).value, 5);
+    assert_eq!(helper(), 11);
+}
+
+// The ignored keys are reported as deprecation warnings, silenced for this module.
+#[allow(deprecated)]
+mod forward {
+    use impl_new_derive::ImplNew;
+
+    #[derive(ImplNew)]
+    #[new(added_later(mode = "x"), ignore_unknown)]
+    pub struct ForwardCompatible {
+        pub id: u8,
+        #[new(newer_option, default(7))]
+        pub level: u8,
+    }
+}
+
+#[test]
+fn ignore_unknown_skips_keys_from_newer_versions() {
+    let value = forward::ForwardCompatible::new(1);
+    assert_eq!((value.id, value.level), (1, 7));
+}
+
+mod visibility {
+    use impl_new_derive::ImplNew;
+
+    #[derive(ImplNew)]
+    pub struct Scoped {
+        pub id: u8,
+        pub(self) own: u8,
+        pub(crate) krate: u8,
+        pub(super) parent: u8,
+        pub(in crate::visibility) path: u8,
+    }
+
+    impl Scoped {
+        pub fn restricted(&self) -> [u8; 4] {
+            [self.own, self.krate, self.parent, self.path]
+        }
+    }
+}
+
+#[test]
+fn restricted_visibilities_are_defaulted_like_private_fields() {
+    let scoped = visibility::Scoped::new(4);
+    assert_eq!(scoped.id, 4);
+    assert_eq!(scoped.restricted(), [0; 4]);
+}
+
+#[derive(ImplNew)]
+#[new(with_overrides)]
+pub struct Rect3 {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+    pub color: u32,
+    pub layer: u8,
+    revision: u32,
+}
+
+#[test]
+fn wide_structs_build_without_clippy_noise() {
+    let rect = Rect3::new(1, 2, 3, 4, 5, 6, 7, 8);
+    assert_eq!((rect.x, rect.y, rect.z), (1, 2, 3));
+    assert_eq!((rect.width, rect.height, rect.depth), (4, 5, 6));
+    assert_eq!((rect.color, rect.layer), (7, 8));
+    let moved = Rect3::with(rect, 0, 0, 0, 4, 5, 6, 7, 8);
+    assert_eq!((moved.x, moved.revision), (0, 0));
+}
+
+#[derive(ImplNew)]
+#[new(rename_all = "camelCase")]
+pub struct Token {
+    #[new(rename = "kind")]
+    pub r#type: u8,
+    #[new(rename = "type")]
+    pub token_kind: u8,
+    pub source_line: u32,
+}
+
+#[test]
+fn field_renames_compose_with_raw_identifiers_and_rename_all() {
+    let token = Token::new(1, 2, 3);
+    assert_eq!(
+        (token.r#type, token.token_kind, token.source_line),
+        (1, 2, 3)
+    );
+}
+
+#[derive(ImplNew)]
+#[new(const)]
+pub struct Limits {
+    pub max: u32,
+    #[new(default(16))]
+    min: u32,
+    #[new(default([0; 4]))]
+    reserved: [u8; 4],
+}
+
+const LIMITS: Limits = Limits::new(64);
+
+#[derive(ImplNew)]
+#[new(const, try_const)]
+pub struct Weights<const N: usize> {
+    pub bias: i32,
+    #[new(default([1; N]))]
+    values: [i32; N],
+}
+
+const WEIGHTS: Weights<3> = Weights::new(-1);
+
+#[derive(ImplNew)]
+#[new(const, try_const)]
+pub struct Journal {
+    pub name: &'static str,
+    entries: Vec<String>,
+}
+
+#[test]
+fn const_constructors_work_in_const_items() {
+    assert_eq!((LIMITS.max, LIMITS.min, LIMITS.reserved), (64, 16, [0; 4]));
+    assert_eq!((WEIGHTS.bias, WEIGHTS.values), (-1, [1; 3]));
+}
+
+#[test]
+fn try_const_falls_back_to_a_runtime_constructor() {
+    let journal = Journal::new("ops");
+    assert_eq!(journal.name, "ops");
+    assert!(journal.entries.is_empty());
+}
+
+#[derive(ImplNew)]
+#[new(extern_c)]
+#[repr(C)]
+pub struct CPoint {
+    pub x: f32,
+    pub y: f32,
+    flags: u32,
+}
+
+#[test]
+fn extern_c_constructors_use_the_c_abi() {
+    let ctor: extern "C" fn(f32, f32) -> CPoint = CPoint::new;
+    let v = ctor(1.0, 2.0);
+    assert_eq!((v.x, v.y, v.flags), (1.0, 2.0, 0));
+}
+
+#[derive(ImplNew, Clone, Copy)]
+#[new(const)]
+#[repr(C, align(16))]
+pub struct Vec4 {
+    pub lanes: [f32; 4],
+}
+
+#[derive(ImplNew)]
+#[repr(align(64))]
+struct CacheLine<T> {
+    pub value: T,
+    hits: u64,
+}
+
+#[test]
+fn aligned_structs_keep_their_alignment() {
+    const ONE: Vec4 = Vec4::new([1.0; 4]);
+    assert_eq!(std::mem::align_of::<Vec4>(), 16);
+    assert_eq!(ONE.lanes, [1.0; 4]);
+
+    let line = CacheLine::new(7u8);
+    assert_eq!(std::mem::align_of_val(&line), 64);
+    assert_eq!(&line as *const _ as usize % 64, 0);
+    assert_eq!((line.value, line.hits), (7, 0));
+}
+
+#[derive(ImplNew)]
+pub struct Ticket {
+    pub id: u32,
+    #[new(caller_location)]
+    pub created_at: &'static std::panic::Location<'static>,
+}
+
+#[test]
+fn caller_location_records_where_new_was_called() {
+    let (ticket, line) = (Ticket::new(1), line!());
+    assert_eq!(ticket.id, 1);
+    assert_eq!(ticket.created_at.file(), file!());
+    assert_eq!(ticket.created_at.line(), line);
+}
+
+#[derive(ImplNew)]
+struct ListNode<T> {
+    pub value: T,
+    pub next: Option<Box<Self>>,
+    prev_len: Option<Box<Self>>,
+}
+
+#[derive(ImplNew)]
+#[new(free_fn = "make_tree")]
+struct Tree {
+    pub label: &'static str,
+    pub children: Vec<Self>,
+    #[new(default(None))]
+    parent_hint: Option<Box<Self>>,
+}
+
+#[test]
+fn self_in_field_types_names_the_struct() {
+    let tail = ListNode::new(2, None);
+    let head = ListNode::new(1, Some(Box::new(tail)));
+    let next = head.next.as_deref().unwrap();
+    assert_eq!((head.value, next.value), (1, 2));
+    assert!(next.next.is_none() && head.prev_len.is_none());
+
+    let tree = make_tree("root", vec![make_tree("leaf", Vec::new())]);
+    assert_eq!(tree.children[0].label, "leaf");
+    assert!(tree.parent_hint.is_none());
+}
+
+#[derive(ImplNew)]
+struct Profiled {
+    pub input: Vec<u32>,
+    #[new(default({
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        input.iter().sum()
+    }))]
+    total: u32,
+    #[new(timed)]
+    built_in: std::time::Duration,
+}
+
+#[test]
+fn timed_fields_record_how_long_new_took() {
+    let profiled = Profiled::new(vec![1, 2, 3]);
+    assert_eq!((profiled.input.len(), profiled.total), (3, 6));
+    assert!(profiled.built_in >= std::time::Duration::from_millis(2));
+    assert!(profiled.built_in < std::time::Duration::from_secs(60));
+}
+
+#[derive(ImplNew)]
+#[new(field_order_check)]
+struct Payment {
+    pub account: &'static str,
+    #[new(group = "amount")]
+    pub units: u64,
+    #[new(group = "amount")]
+    pub cents: u8,
+    entries: Vec<u64>,
+    pub memo: &'static str,
+}
+
+#[test]
+fn field_order_check_accepts_the_declaration_order() {
+    let payment = Payment::new("ops", (12, 50), "rent");
+    assert_eq!(
+        (payment.account, payment.units, payment.cents),
+        ("ops", 12, 50)
+    );
+    assert_eq!((payment.entries.len(), payment.memo), (0, "rent"));
+}
@@ -0,0 +1,871 @@
+use impl_new_derive::ImplNew;
+
+#[derive(Debug, PartialEq)]
+struct Registry {
+    slots: usize,
+}
+
+impl Registry {
+    fn new() -> Self {
+        Self { slots: 8 }
+    }
+}
+
+#[derive(ImplNew)]
+struct Service {
+    pub name: String,
+    #[new(default(Registry::new()))]
+    registry: Registry,
+    #[new(default(Self::default_retries()))]
+    retries: u32,
+    #[new(default(Self::DEFAULT_TIMEOUT))]
+    timeout: u64,
+}
+
+impl Service {
+    const DEFAULT_TIMEOUT: u64 = 30;
+
+    fn default_retries() -> u32 {
+        3
+    }
+}
+
+#[test]
+fn default_can_call_other_constructors() {
+    let service = Service::new("api".to_string());
+    assert_eq!(service.name, "api");
+    assert_eq!(service.registry, Registry { slots: 8 });
+}
+
+#[test]
+fn default_can_call_own_associated_items() {
+    let service = Service::new("api".to_string());
+    assert_eq!(service.retries, 3);
+    assert_eq!(service.timeout, 30);
+}
+
+#[derive(ImplNew)]
+struct Node {
+    pub id: u32,
+    #[new(default(Vec::new()))]
+    children: Vec<Node>,
+    #[new(default(Node::leaf_count()))]
+    leaves: usize,
+}
+
+impl Node {
+    fn leaf_count() -> usize {
+        1
+    }
+
+    fn with_child(id: u32, child: Node) -> Self {
+        let mut node = Self::new(id);
+        node.children.push(child);
+        node
+    }
+}
+
+#[test]
+fn self_referential_defaults_compile() {
+    let node = Node::with_child(1, Node::new(2));
+    assert_eq!(node.id, 1);
+    assert_eq!(node.children[0].id, 2);
+    assert_eq!(node.leaves, 1);
+}
+
+#[derive(ImplNew)]
+struct Counter {
+    pub label: &'static str,
+    #[new(default(10))]
+    pub start: u32,
+}
+
+#[test]
+fn public_fields_with_default_are_not_parameters() {
+    let counter = Counter::new("hits");
+    assert_eq!(counter.label, "hits");
+    assert_eq!(counter.start, 10);
+}
+
+#[derive(ImplNew)]
+struct Backend {
+    pub name: &'static str,
+    #[cfg_attr(all(), new(default(Backend::fast())))]
+    #[cfg_attr(not(all()), new(default(Backend::slow())))]
+    strategy: &'static str,
+    #[cfg_attr(any(), new(default(Backend::fast())))]
+    #[cfg_attr(not(any()), new(default(Backend::slow())))]
+    fallback: &'static str,
+}
+
+impl Backend {
+    fn fast() -> &'static str {
+        "fast"
+    }
+
+    fn slow() -> &'static str {
+        "slow"
+    }
+}
+
+#[test]
+fn cfg_attr_selects_the_active_default() {
+    let backend = Backend::new("db");
+    assert_eq!(backend.name, "db");
+    assert_eq!(backend.strategy, "fast");
+    assert_eq!(backend.fallback, "slow");
+}
+
+const ZERO_PAGE: [u8; 64] = [0; 64];
+
+#[derive(ImplNew)]
+struct Frame {
+    pub id: u16,
+    #[new(default([0; 4096]))]
+    buffer: [u8; 4096],
+    #[new(default(ZERO_PAGE))]
+    header: [u8; 64],
+    #[new(default([[1.0; 4]; 4]))]
+    transform: [[f32; 4]; 4],
+}
+
+#[test]
+fn array_defaults_are_assigned_in_place() {
+    let frame = Frame::new(7);
+    assert_eq!(frame.id, 7);
+    assert!(frame.buffer.iter().all(|&b| b == 0));
+    assert_eq!(frame.header, ZERO_PAGE);
+    assert_eq!(frame.transform, [[1.0; 4]; 4]);
+}
+
+#[derive(ImplNew)]
+#[new(expose_defaults)]
+struct Client {
+    pub url: &'static str,
+    #[new(default(30))]
+    request_timeout: u64,
+    #[new(default(-1))]
+    max_redirects: i32,
+    #[new(default("impl-new"))]
+    user_agent: &'static str,
+    #[new(default(Vec::new()))]
+    headers: Vec<(String, String)>,
+}
+
+#[test]
+fn literal_defaults_are_exposed_as_constants() {
+    assert_eq!(Client::REQUEST_TIMEOUT_DEFAULT, 30);
+    assert_eq!(Client::MAX_REDIRECTS_DEFAULT, -1);
+    assert_eq!(Client::USER_AGENT_DEFAULT, "impl-new");
+
+    let client = Client::new("http://localhost");
+    assert_eq!(client.url, "http://localhost");
+    assert_eq!(client.request_timeout, Client::REQUEST_TIMEOUT_DEFAULT);
+    assert_eq!(client.max_redirects, -1);
+    assert_eq!(client.user_agent, "impl-new");
+    assert!(client.headers.is_empty());
+}
+
+#[derive(ImplNew)]
+struct Labeled {
+    pub id: u8,
+    #[new(default((1, "x".to_string())))]
+    pair: (i32, String),
+    #[new(default((0.5,)))]
+    single: (f32,),
+}
+
+#[test]
+fn tuple_fields_take_parenthesized_defaults() {
+    let labeled = Labeled::new(4);
+    assert_eq!(labeled.id, 4);
+    assert_eq!(labeled.pair, (1, "x".to_string()));
+    assert_eq!(labeled.single, (0.5,));
+}
+
+#[derive(Debug, PartialEq, ImplNew)]
+#[new(const_default)]
+struct Limits {
+    #[new(default(16))]
+    pub max_connections: u32,
+    #[new(default("default"))]
+    profile: &'static str,
+    #[new(default([0; 4]))]
+    reserved: [u8; 4],
+    marker: std::marker::PhantomData<u8>,
+}
+
+const LIMITS: Limits = Limits::DEFAULT;
+static STATIC_LIMITS: Limits = Limits::DEFAULT;
+
+#[test]
+fn const_default_is_usable_in_const_context() {
+    assert_eq!(LIMITS.max_connections, 16);
+    assert_eq!(STATIC_LIMITS.profile, "default");
+    assert_eq!(LIMITS.reserved, [0; 4]);
+    assert_eq!(Limits::new(), Limits::DEFAULT);
+}
+
+#[derive(Debug, PartialEq, ImplNew)]
+#[new(const_default)]
+struct Geometry {
+    #[new(default(4))]
+    width: u32,
+    #[new(default(3))]
+    height: u32,
+    #[new(default(width * height))]
+    area: u32,
+}
+
+const GEOMETRY: Geometry = Geometry::DEFAULT;
+
+#[test]
+fn const_default_sees_the_earlier_fields() {
+    assert_eq!((GEOMETRY.width, GEOMETRY.height, GEOMETRY.area), (4, 3, 12));
+    assert_eq!(Geometry::new(), Geometry::DEFAULT);
+}
+
+struct SharedDefaults {
+    timeout: u64,
+    retries: u8,
+    host: &'static str,
+}
+
+const SHARED: SharedDefaults = SharedDefaults {
+    timeout: 30,
+    retries: 3,
+    host: "localhost",
+};
+
+#[derive(ImplNew)]
+struct HttpConfig {
+    pub path: &'static str,
+    #[new(default(SHARED.timeout))]
+    timeout: u64,
+    #[new(default(SHARED.host.to_string()))]
+    host: String,
+}
+
+#[derive(ImplNew)]
+struct DbConfig {
+    #[new(default(SHARED.timeout * 2))]
+    timeout: u64,
+    #[new(default(SHARED.retries))]
+    pub retries: u8,
+}
+
+#[test]
+fn field_access_defaults_share_values_across_structs() {
+    let http = HttpConfig::new("/health");
+    assert_eq!(http.path, "/health");
+    assert_eq!(http.timeout, 30);
+    assert_eq!(http.host, "localhost");
+
+    let db = DbConfig::new();
+    assert_eq!(db.timeout, 60);
+    assert_eq!(db.retries, 3);
+}
+
+#[derive(ImplNew)]
+struct BuildInfo {
+    pub profile: &'static str,
+    #[new(default(env!("CARGO_PKG_NAME").to_string()))]
+    package: String,
+    #[new(default(option_env!("IMPL_NEW_UNSET_VARIABLE").unwrap_or("none")))]
+    build_id: &'static str,
+    #[new(default(concat!("v", env!("CARGO_PKG_VERSION"))))]
+    version: &'static str,
+    #[new(default(include_str!("fixtures/banner.txt").trim_end()))]
+    banner: &'static str,
+}
+
+#[test]
+fn macro_call_defaults_are_emitted_verbatim() {
+    let info = BuildInfo::new("release");
+    assert_eq!(info.profile, "release");
+    assert_eq!(info.package, "impl-new-derive");
+    assert_eq!(info.build_id, "none");
+    assert_eq!(info.version, concat!("v", env!("CARGO_PKG_VERSION")));
+    assert_eq!(info.banner, "welcome aboard");
+}
+
+// Paths are resolved relative to this file, where the default expression is written.
+#[derive(ImplNew)]
+#[new(const)]
+struct Embedded {
+    pub name: &'static str,
+    #[new(default(include_bytes!("fixtures/magic.bin")))]
+    magic: &'static [u8],
+    #[new(default(*include_bytes!("fixtures/magic.bin")))]
+    header: [u8; 6],
+    #[new(default(include_str!("fixtures/banner.txt")))]
+    banner: &'static str,
+}
+
+#[test]
+fn embedded_data_defaults_read_fixture_files() {
+    const EMBEDDED: Embedded = Embedded::new("logo");
+    assert_eq!(EMBEDDED.name, "logo");
+    assert_eq!(EMBEDDED.magic, b"\x89PNG\x00\x01");
+    assert_eq!(EMBEDDED.header, *b"\x89PNG\x00\x01");
+    assert_eq!(EMBEDDED.banner.trim_end(), "welcome aboard");
+}
+
+#[derive(ImplNew)]
+#[new(const_default)]
+struct Pool<T> {
+    #[new(default(Self::DEFAULT_CAP))]
+    capacity: usize,
+    #[new(default(Self::DEFAULT_CAP * 2))]
+    limit: usize,
+    marker: std::marker::PhantomData<T>,
+}
+
+impl<T> Pool<T> {
+    const DEFAULT_CAP: usize = 4;
+}
+
+#[test]
+fn self_relative_const_defaults_resolve_in_every_generated_item() {
+    let pool = Pool::<String>::new();
+    assert_eq!((pool.capacity, pool.limit), (4, 8));
+
+    const POOL: Pool<u8> = Pool::DEFAULT;
+    assert_eq!((POOL.capacity, POOL.limit), (4, 8));
+}
+
+#[derive(Debug, PartialEq)]
+struct Meters(f64);
+
+impl From<u32> for Meters {
+    fn from(value: u32) -> Self {
+        Meters(f64::from(value))
+    }
+}
+
+#[derive(ImplNew)]
+struct Route {
+    pub id: u8,
+    #[new(default("unnamed".into()))]
+    name: String,
+    #[new(default(3u32.into()))]
+    length: Meters,
+    #[new(default(From::from(1.5f32)))]
+    speed: f64,
+    #[new(default([1u8, 2].into()))]
+    stops: Vec<u8>,
+}
+
+#[test]
+fn into_defaults_take_the_field_type_as_target() {
+    let route = Route::new(1);
+    assert_eq!(route.id, 1);
+    assert_eq!(route.name, "unnamed");
+    assert_eq!(route.length, Meters(3.0));
+    assert_eq!(route.speed, 1.5);
+    assert_eq!(route.stops, [1, 2]);
+}
+
+#[derive(ImplNew)]
+#[new(eager_default)]
+struct Throttle {
+    pub name: &'static str,
+    #[new(default(250))]
+    interval_ms: u64,
+    #[new(default(-3))]
+    offset: i8,
+    #[new(default(String::from("burst")))]
+    mode: String,
+}
+
+#[derive(ImplNew)]
+#[new(eager_default, free_fn = "make_window")]
+struct Window<T> {
+    #[new(default(16))]
+    size: usize,
+    pub items: Vec<T>,
+}
+
+#[test]
+fn eager_defaults_are_read_from_constants() {
+    let throttle = Throttle::new("api");
+    assert_eq!(throttle.name, "api");
+    assert_eq!(throttle.interval_ms, 250);
+    assert_eq!(throttle.offset, -3);
+    assert_eq!(throttle.mode, "burst");
+
+    let window = make_window(vec!['a']);
+    assert_eq!((window.size, window.items), (16, vec!['a']));
+}
+
+fn compute_checksum(name: &str, age: u32) -> u64 {
+    name.bytes().map(u64::from).sum::<u64>() + u64::from(age)
+}
+
+#[derive(ImplNew)]
+struct Profile {
+    pub name: String,
+    pub age: u32,
+    #[new(default(compute_checksum(&name, age)))]
+    checksum: u64,
+    #[new(default(format!("{name}#{checksum}")))]
+    tag: String,
+}
+
+#[test]
+fn defaults_can_borrow_parameters_and_earlier_defaults() {
+    let profile = Profile::new("ab".to_string(), 3);
+    assert_eq!(profile.name, "ab");
+    assert_eq!(profile.age, 3);
+    assert_eq!(profile.checksum, 97 + 98 + 3);
+    assert_eq!(profile.tag, "ab#198");
+}
+
+#[derive(ImplNew)]
+struct Sum {
+    pub a: i32,
+    pub b: i32,
+    #[new(default(a + b))]
+    c: i32,
+    #[new(default(c * scale))]
+    scaled: i32,
+    pub scale: i32,
+}
+
+#[test]
+fn defaults_are_computed_in_field_order() {
+    let sum = Sum::new(2, 3, 10);
+    assert_eq!((sum.a, sum.b, sum.c), (2, 3, 5));
+    assert_eq!((sum.scaled, sum.scale), (50, 10));
+}
+
+#[derive(ImplNew)]
+struct Tuning {
+    pub name: &'static str,
+    #[new(default(std::env::var("IMPL_NEW_UNSET_VARIABLE").ok().and_then(|s| s.parse().ok()).unwrap_or(42)))]
+    threads: u32,
+    #[new(default(["a", "bb", "ccc"].iter().map(|s| s.len()).filter(|&n| n > 1).fold(String::new(), |acc, n| format!("{acc}{n}")).len()))]
+    weight: usize,
+    #[new(default(std::collections::HashMap::<String, u8>::with_capacity(2).into_iter().collect::<Vec<(String, u8)>>()))]
+    pairs: Vec<(String, u8)>,
+    #[new(default(Some(3).map_or_else(|| 0, |n: u8| n.pow(2)).checked_add(1).unwrap_or_default()))]
+    squared: u8,
+}
+
+#[test]
+fn long_method_chains_are_emitted_verbatim() {
+    let tuning = Tuning::new("chain");
+    assert_eq!(tuning.name, "chain");
+    assert_eq!(tuning.threads, 42);
+    assert_eq!(tuning.weight, 2);
+    assert!(tuning.pairs.is_empty());
+    assert_eq!(tuning.squared, 10);
+}
+
+#[derive(Debug, PartialEq, ImplNew)]
+struct Pager {
+    #[new(default(20))]
+    pub per_page: u32,
+    cursor: Option<u64>,
+}
+
+#[derive(ImplNew)]
+struct Catalog {
+    pub title: &'static str,
+    pub items: Vec<u32>,
+}
+
+#[derive(ImplNew)]
+struct Listing {
+    pub query: &'static str,
+    #[new(nested_default)]
+    pager: Pager,
+    #[new(default(Catalog::new("featured", vec![1, 2])))]
+    featured: Catalog,
+}
+
+#[test]
+fn private_fields_can_be_built_by_other_derived_constructors() {
+    let listing = Listing::new("rust");
+    assert_eq!(listing.query, "rust");
+    assert_eq!(listing.pager, Pager::new());
+    assert_eq!(listing.pager.cursor, None);
+    assert_eq!(listing.featured.title, "featured");
+    assert_eq!(listing.featured.items, [1, 2]);
+}
+
+#[derive(ImplNew)]
+struct Worker<T> {
+    pub job: T,
+    #[new(type_name)]
+    kind: &'static str,
+    #[new(type_name)]
+    pub owned_kind: String,
+    #[new(type_name)]
+    shared_kind: std::sync::Arc<str>,
+}
+
+#[test]
+fn type_name_fields_hold_the_struct_name() {
+    let worker = Worker::new(5);
+    assert_eq!(worker.job, 5);
+    assert_eq!(worker.kind, "Worker");
+    assert_eq!(worker.owned_kind, "Worker");
+    assert_eq!(&*worker.shared_kind, "Worker");
+}
+
+#[derive(Debug, PartialEq, ImplNew)]
+#[new(default)]
+struct Preferences {
+    pub theme: String,
+    #[new(default(14))]
+    pub font_size: u8,
+    history: Vec<String>,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            theme: "light".to_string(),
+            font_size: 12,
+            history: vec!["welcome".to_string()],
+        }
+    }
+}
+
+#[test]
+fn field_defaults_override_the_struct_level_default() {
+    let preferences = Preferences::new();
+    assert_eq!(preferences.font_size, 14);
+    assert_eq!(preferences.theme, "light");
+    assert_eq!(preferences.history, ["welcome"]);
+}
+
+#[derive(ImplNew)]
+struct Precedence {
+    pub id: u8,
+    #[new(default, default(3))]
+    pub explicit_after_bare: u8,
+    #[new(default(4), default)]
+    pub bare_after_explicit: u8,
+    #[new(default)]
+    pub bare: u8,
+}
+
+#[test]
+fn explicit_field_defaults_override_bare_ones() {
+    let value = Precedence::new(1);
+    assert_eq!(value.id, 1);
+    assert_eq!(value.explicit_after_bare, 3);
+    assert_eq!(value.bare_after_explicit, 4);
+    assert_eq!(value.bare, 0);
+}
+
+#[derive(ImplNew)]
+struct Sampler {
+    pub name: &'static str,
+    #[new(default(if cfg!(debug_assertions) { 100 } else { 1000 }))]
+    interval_ms: u64,
+    #[new(default(cfg!(test)))]
+    in_tests: bool,
+}
+
+#[test]
+fn cfg_macro_defaults_are_evaluated_at_runtime() {
+    let sampler = Sampler::new("cpu");
+    assert_eq!(sampler.name, "cpu");
+    let expected = if cfg!(debug_assertions) { 100 } else { 1000 };
+    assert_eq!(sampler.interval_ms, expected);
+    assert!(sampler.in_tests);
+}
+
+#[derive(ImplNew)]
+struct Theme {
+    pub name: &'static str,
+    accent: u32,
+    #[new(default(2))]
+    spacing: u8,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            name: "default",
+            accent: 0xff8800,
+            spacing: 8,
+        }
+    }
+}
+
+#[test]
+fn new_uses_per_field_defaults_not_the_struct_default_impl() {
+    let theme = Theme::new("dark");
+    assert_eq!(theme.name, "dark");
+    assert_eq!(theme.accent, 0);
+    assert_eq!(theme.spacing, 2);
+    assert_eq!(Theme::default().accent, 0xff8800);
+}
+
+fn default_retries() -> u8 {
+    3
+}
+
+#[derive(ImplNew)]
+struct Crawler {
+    pub seed: &'static str,
+    #[new(default_fn = "Vec::new")]
+    queue: Vec<String>,
+    #[new(default_fn = "std::collections::HashMap::<String, u32>::new")]
+    visits: std::collections::HashMap<String, u32>,
+    #[new(default_fn = "default_retries")]
+    pub retries: u8,
+    #[new(default_fn = "<u16 as Default>::default")]
+    depth: u16,
+}
+
+#[test]
+fn default_fn_calls_the_function_without_arguments() {
+    let crawler = Crawler::new("https://example.org");
+    assert_eq!(crawler.seed, "https://example.org");
+    assert!(crawler.queue.is_empty());
+    assert!(crawler.visits.is_empty());
+    assert_eq!(crawler.retries, 3);
+    assert_eq!(crawler.depth, 0);
+}
+
+#[derive(ImplNew)]
+#[impl_new(try_new)]
+struct Namespaced {
+    pub name: &'static str,
+    #[impl_new(default(8))]
+    pub workers: usize,
+    #[impl_new(default(name.len()))]
+    name_len: usize,
+    #[new(default(true))]
+    enabled: bool,
+}
+
+#[test]
+fn impl_new_is_an_alias_namespace_for_new_options() {
+    let value = Namespaced::try_new("jobs").unwrap();
+    assert_eq!((value.name, value.workers), ("jobs", 8));
+    assert_eq!(value.name_len, 4);
+    assert!(value.enabled);
+}
+
+#[derive(ImplNew)]
+#[new(defaults_from = "SHARED")]
+struct CacheConfig {
+    pub capacity: usize,
+    timeout: u64,
+    #[new(default(5))]
+    retries: u8,
+    host: &'static str,
+}
+
+#[derive(ImplNew)]
+#[new(defaults_from = "crate::SHARED")]
+struct QueueConfig {
+    timeout: u64,
+    #[new(default)]
+    pub retries: u8,
+}
+
+#[test]
+fn defaults_from_reads_like_named_fields_of_a_const() {
+    let cache = CacheConfig::new(128);
+    assert_eq!(cache.capacity, 128);
+    assert_eq!(
+        (cache.timeout, cache.retries, cache.host),
+        (30, 5, "localhost")
+    );
+
+    let queue = QueueConfig::new();
+    assert_eq!((queue.timeout, queue.retries), (30, 3));
+}
+
+#[derive(ImplNew)]
+#[new(const)]
+struct Lazy {
+    pub key: &'static str,
+    rendered: std::cell::OnceCell<String>,
+    shared: std::sync::OnceLock<Vec<u8>>,
+}
+
+// Evaluated at compile time, which `Lazy::new` only allows if it is a `const fn`.
+const _: () = core::mem::forget(Lazy::new("key"));
+
+#[test]
+fn once_cells_start_out_empty() {
+    let lazy = Lazy::new("other");
+    assert_eq!(
+        lazy.rendered.get_or_init(|| lazy.key.to_uppercase()),
+        "OTHER"
+    );
+    assert!(lazy.shared.get().is_none());
+}
+
+#[derive(ImplNew)]
+struct Units {
+    pub unit: (),
+    #[new(default(()))]
+    explicit: (),
+    #[new(default)]
+    pub skipped: (),
+}
+
+#[derive(ImplNew)]
+struct ImplicitUnit {
+    pub id: u8,
+    implicit: (),
+}
+
+#[test]
+fn unit_fields_are_parameters_or_defaults() {
+    let units = Units::new(());
+    assert_eq!((units.unit, units.explicit, units.skipped), ((), (), ()));
+    let implicit = ImplicitUnit::new(3);
+    assert_eq!((implicit.id, implicit.implicit), (3, ()));
+}
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+#[derive(ImplNew)]
+struct Canvas {
+    pub name: &'static str,
+    #[new(default(Box::new(Square(2.0))))]
+    shape: Box<dyn Shape>,
+    #[new(default(std::sync::Arc::new(|x| x + 1)))]
+    step: std::sync::Arc<dyn Fn(u32) -> u32 + Send + Sync>,
+    #[new(default(&Square(1.0)))]
+    unit: &'static dyn Shape,
+    #[new(default(vec![Box::new(Square(3.0)) as Box<dyn Shape>]))]
+    layers: Vec<Box<dyn Shape>>,
+}
+
+#[test]
+fn trait_object_defaults_are_unsized_coerced() {
+    let canvas = Canvas::new("canvas");
+    assert_eq!(canvas.name, "canvas");
+    assert_eq!(canvas.shape.area(), 4.0);
+    assert_eq!((canvas.step)(1), 2);
+    assert_eq!(canvas.unit.area(), 1.0);
+    assert_eq!(canvas.layers[0].area(), 9.0);
+}
+
+// Clippy takes the repeated `cap = <integer>` for a duplicated attribute.
+#[allow(clippy::duplicated_attributes)]
+#[derive(Debug, ImplNew)]
+#[new(profile(name = "fast", defaults(cap = 16, label = "fast")))]
+#[new(profile(name = "big", defaults(cap = 4096, label = "big", spill = true)))]
+struct RingBuffer {
+    pub cap: usize,
+    pub label: &'static str,
+    #[new(default(false))]
+    spill: bool,
+    items: Vec<u8>,
+}
+
+#[test]
+fn profiles_are_preset_constructors() {
+    let fast = RingBuffer::fast();
+    assert_eq!((fast.cap, fast.label, fast.spill), (16, "fast", false));
+    let big = RingBuffer::big();
+    assert_eq!((big.cap, big.label, big.spill), (4096, "big", true));
+    assert!(big.items.is_empty());
+
+    let custom = RingBuffer::new(8, "custom");
+    assert_eq!((custom.cap, custom.spill), (8, false));
+}
+
+#[derive(ImplNew)]
+#[new(profile(name = "origin", defaults(x = 0, y = 0, tags = ["home"])))]
+struct Waypoint {
+    #[new(group = "pos")]
+    pub x: i32,
+    #[new(group = "pos")]
+    pub y: i32,
+    #[new(from_iter)]
+    pub tags: Vec<&'static str>,
+}
+
+#[test]
+fn profiles_fill_grouped_and_converted_parameters() {
+    let origin = Waypoint::origin();
+    assert_eq!((origin.x, origin.y), (0, 0));
+    assert_eq!(origin.tags, ["home"]);
+}
+
+fn helper(x: u32) -> u32 {
+    x * 10
+}
+
+fn seed() -> u8 {
+    7
+}
+
+#[derive(ImplNew)]
+#[new(post = "value.total += u32::from(seed())")]
+struct Shadowing {
+    #[new(convert(helper(x)))]
+    pub x: u32,
+    #[new(default(seed()))]
+    seed: u8,
+    #[new(default(helper(1)))]
+    helper: u32,
+    #[new(default(x + helper(2)))]
+    total: u32,
+}
+
+#[test]
+fn defaulted_fields_do_not_shadow_items_used_elsewhere() {
+    let value = Shadowing::new(3);
+    assert_eq!((value.x, value.seed, value.helper), (30, 7, 10));
+    assert_eq!(value.total, 3 + 20 + 7);
+}
+
+#[derive(ImplNew)]
+#[new(
+    optional_params,
+    profile(name = "wide", defaults(name = "wide", columns = 132))
+)]
+#[new(profile(name = "plain", defaults(name = "plain")))]
+struct Terminal {
+    pub name: &'static str,
+    #[new(default(80))]
+    pub columns: u16,
+    #[new(default(24))]
+    pub rows: u16,
+}
+
+#[test]
+fn profiles_wrap_optional_parameters() {
+    let wide = Terminal::wide();
+    assert_eq!((wide.name, wide.columns, wide.rows), ("wide", 132, 24));
+    let plain = Terminal::plain();
+    assert_eq!((plain.name, plain.columns, plain.rows), ("plain", 80, 24));
+}
+
+#[derive(ImplNew)]
+#[new(post = "value.total = value.count * 2")]
+#[new(profile(name = "busy", defaults(count = 8)))]
+struct Tally {
+    pub count: u32,
+    total: u32,
+}
+
+#[test]
+fn profile_parameters_go_through_post() {
+    let busy = Tally::busy();
+    assert_eq!((busy.count, busy.total), (8, 16));
+}
@@ -0,0 +1,475 @@
+//! Expansion tests, run against the macro's own modules so the generated tokens can be inspected
+//! without going through the compiler.
+
+#![allow(dead_code)]
+
+#[path = "../src/attr.rs"]
+mod attr;
+#[path = "../src/case.rs"]
+mod case;
+#[path = "../src/checked_builder.rs"]
+mod checked_builder;
+#[path = "../src/docs.rs"]
+mod docs;
+#[path = "../src/expand.rs"]
+mod expand;
+#[path = "../src/named_args.rs"]
+mod named_args;
+#[path = "../src/ty.rs"]
+mod ty;
+#[path = "../src/typed_builder.rs"]
+mod typed_builder;
+
+use syn::{parse_quote, DeriveInput};
+
+fn expand(input: DeriveInput) -> String {
+    expand::derive(input).unwrap().to_string()
+}
+
+#[test]
+fn impl_is_marked_automatically_derived() {
+    let expanded = expand(parse_quote! {
+        struct Point<T> {
+            pub x: T,
+            pub y: T,
+        }
+    });
+    assert!(
+        expanded.starts_with("# [automatically_derived] impl < T > Point < T >"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn rename_all_renames_parameters_but_not_fields() {
+    let expanded = expand(parse_quote! {
+        #[new(rename_all = "PascalCase")]
+        struct Binding {
+            pub request_timeout: u64,
+            pub r#type: u8,
+        }
+    });
+    assert!(
+        expanded.contains("fn new (RequestTimeout : u64 , Type : u8)"),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains("request_timeout : RequestTimeout , r#type : Type"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn init_fields_take_named_closure_parameters() {
+    let expanded = expand(parse_quote! {
+        struct Session {
+            #[new(init)]
+            token: String,
+            #[new(init = "clock")]
+            started: u64,
+        }
+    });
+    assert!(
+        expanded.contains(
+            "token_init : impl :: core :: ops :: FnOnce () -> String , \
+             clock : impl :: core :: ops :: FnOnce () -> u64"
+        ),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn zero_parameter_new_allows_new_without_default() {
+    let expanded = expand(parse_quote! {
+        pub struct Registry {
+            entries: Vec<u8>,
+        }
+    });
+    assert!(
+        expanded.contains("# [allow (clippy :: new_without_default)] pub fn new ()"),
+        "{expanded}"
+    );
+
+    let expanded = expand(parse_quote! {
+        pub struct Point {
+            pub x: i32,
+        }
+    });
+    assert!(!expanded.contains("new_without_default"), "{expanded}");
+}
+
+#[test]
+fn eager_default_hoists_literal_defaults_only() {
+    let expanded = expand(parse_quote! {
+        #[new(eager_default)]
+        struct Throttle {
+            #[new(default(250))]
+            interval_ms: u64,
+            #[new(default(String::new()))]
+            mode: String,
+        }
+    });
+    assert!(
+        expanded.contains("interval_ms : Self :: __DEFAULT_INTERVAL_MS"),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains("# [doc (hidden)] const __DEFAULT_INTERVAL_MS : u64 = 250 ;"),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains("let default_mode : String = String :: new () ;"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn defaults_are_bound_before_the_struct_literal() {
+    let expanded = expand(parse_quote! {
+        struct Package {
+            pub name: String,
+            #[new(default(checksum(&name)))]
+            checksum: u32,
+        }
+    });
+    assert!(
+        expanded.contains(
+            "let default_checksum : u32 = checksum (& name) ; \
+             Self { name , checksum : default_checksum }"
+        ),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn defaults_without_calls_or_locals_are_written_in_place() {
+    let expanded = expand(parse_quote! {
+        struct Frame {
+            pub id: u32,
+            #[new(default([0; 4096]))]
+            pixels: [u8; 4096],
+        }
+    });
+    assert!(
+        expanded.contains("Self { id , pixels : [0 ; 4096] }"),
+        "{expanded}"
+    );
+    assert!(!expanded.contains("let"), "{expanded}");
+}
+
+#[test]
+fn only_fields_later_defaults_mention_are_visible_by_name() {
+    let expanded = expand(parse_quote! {
+        struct Sum {
+            pub a: i32,
+            #[new(default(a * 2))]
+            doubled: i32,
+            #[new(default(doubled + 1))]
+            next: i32,
+        }
+    });
+    assert!(
+        expanded.contains(
+            "let (default_doubled , default_next ,) = { \
+             let doubled : i32 = a * 2 ; let default_next : i32 = doubled + 1 ; \
+             (doubled , default_next ,) } ;"
+        ),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn wide_constructors_allow_too_many_arguments() {
+    let expanded = expand(parse_quote! {
+        struct Wide {
+            pub a: u8,
+            pub b: u8,
+            pub c: u8,
+            pub d: u8,
+            pub e: u8,
+            pub f: u8,
+            pub g: u8,
+            pub h: u8,
+        }
+    });
+    assert!(
+        expanded.contains("# [allow (clippy :: too_many_arguments)] pub fn new ("),
+        "{expanded}"
+    );
+
+    let expanded = expand(parse_quote! {
+        struct Narrow {
+            pub a: u8,
+            pub b: u8,
+            pub c: u8,
+            pub d: u8,
+            pub e: u8,
+            pub f: u8,
+            pub g: u8,
+        }
+    });
+    assert!(!expanded.contains("too_many_arguments"), "{expanded}");
+}
+
+#[test]
+fn field_rename_names_the_parameter_only() {
+    let expanded = expand(parse_quote! {
+        struct Token {
+            #[new(rename = "kind")]
+            pub r#type: u8,
+            #[new(rename = "type")]
+            pub token_kind: u8,
+        }
+    });
+    assert!(
+        expanded.contains("fn new (kind : u8 , r#type : u8)"),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains("r#type : kind , token_kind : r#type"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn try_const_emits_const_only_when_every_initializer_can_be() {
+    let expanded = expand(parse_quote! {
+        #[new(const, try_const)]
+        struct Limits {
+            pub max: u32,
+            #[new(default(16))]
+            min: u32,
+        }
+    });
+    assert!(
+        expanded.contains("pub const fn new (max : u32)"),
+        "{expanded}"
+    );
+
+    let expanded = expand(parse_quote! {
+        #[new(const, try_const)]
+        struct Journal {
+            pub name: &'static str,
+            entries: Vec<String>,
+        }
+    });
+    assert!(expanded.contains("pub fn new (name :"), "{expanded}");
+
+    let expanded = expand(parse_quote! {
+        #[new(const, try_const)]
+        struct Cache {
+            #[new(default(Vec::with_capacity(8)))]
+            entries: Vec<String>,
+        }
+    });
+    assert!(!expanded.contains("const fn"), "{expanded}");
+}
+
+#[test]
+fn where_predicates_are_kept_and_default_bounds_added_once() {
+    let expanded = expand(parse_quote! {
+        struct Pair<T, U>
+        where
+            T: Clone,
+            T: Send,
+            U: ::core::default::Default,
+        {
+            pub value: T,
+            first: U,
+            second: U,
+            spare: T,
+        }
+    });
+    assert!(
+        expanded.contains(
+            "where T : Clone , T : Send , U : :: core :: default :: Default , \
+             T : :: core :: default :: Default {"
+        ),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn doc_args_lists_parameters_and_defaults() {
+    let expanded = expand(parse_quote! {
+        #[new(doc_args)]
+        struct Connection {
+            pub host: String,
+            #[new(from_iter)]
+            pub tags: Vec<u8>,
+            #[new(group = "pos")]
+            pub x: i32,
+            #[new(group = "pos")]
+            pub y: i32,
+            #[new(default(30))]
+            timeout: u64,
+        }
+    });
+    assert!(
+        expanded.contains(
+            "# [doc = \" # Arguments\"] # [doc = \"\"] \
+             # [doc = \" - `host`: `String`\"] \
+             # [doc = \" - `tags`: `impl :: core :: iter :: IntoIterator < Item = u8 >`\"] \
+             # [doc = \" - `(x, y)`: `(i32, i32)`\"] # [doc = \"\"] \
+             # [doc = \" # Defaults\"]"
+        ),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains("# [doc = \" - `timeout`: `30`\"]"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn builder_setters_and_build_are_must_use() {
+    let expanded = expand(parse_quote! {
+        #[new(builder)]
+        struct Server {
+            pub host: String,
+            #[new(default(80))]
+            pub port: u16,
+        }
+    });
+    assert!(
+        expanded.contains("# [must_use] pub fn with_port (mut self , port : u16) -> Self"),
+        "{expanded}"
+    );
+
+    let expanded = expand(parse_quote! {
+        #[new(typed_builder)]
+        struct Server {
+            pub host: String,
+        }
+    });
+    assert!(
+        expanded.contains("# [must_use] struct ServerBuilder"),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains("# [must_use] pub fn build (self) -> Server"),
+        "{expanded}"
+    );
+
+    // `Result` is already `#[must_use]`, and repeating it would trip `clippy::double_must_use`.
+    let expanded = expand(parse_quote! {
+        #[new(typed_builder, try_new)]
+        struct Server {
+            pub host: String,
+        }
+    });
+    assert!(
+        expanded.contains("pub fn build (self) -> :: core :: result :: Result"),
+        "{expanded}"
+    );
+    assert!(
+        !expanded.contains("# [must_use] pub fn build (self)"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn skip_default_bound_leaves_out_the_field_bound() {
+    let expanded = expand(parse_quote! {
+        struct Service<T: Config, U> {
+            #[new(skip_default_bound)]
+            config: T,
+            extra: U,
+        }
+    });
+    assert!(
+        expanded.contains(
+            "impl < T : Config , U > Service < T , U > where U : :: core :: default :: Default"
+        ),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn param_attr_is_put_on_single_and_grouped_parameters() {
+    let expanded = expand(parse_quote! {
+        struct Rect {
+            #[new(param_attr = "allow(unused_variables), doc = \"Width.\"")]
+            pub width: u32,
+            #[new(group = "origin", param_attr = "allow(non_snake_case)")]
+            pub x: i32,
+            #[new(group = "origin")]
+            pub y: i32,
+        }
+    });
+    assert!(
+        expanded.contains("# [allow (unused_variables)] # [doc = \"Width.\"] width : u32"),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains("# [allow (non_snake_case)] (x , y ,) : (i32 , i32 ,)"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn doc_sections_cannot_open_code_blocks() {
+    let expanded = expand(parse_quote! {
+        #[new(doc_defaults)]
+        struct Snippet {
+            pub lang: String,
+            #[new(default(String::from("```rust\nassert!(false);\n```")))]
+            body: String,
+        }
+    });
+    assert!(
+        expanded.contains(
+            "# [doc = \" - `body`: ````String :: from (\\\"```rust\\\\nassert!(false);\\\\n```\\\")````\"]"
+        ),
+        "{expanded}"
+    );
+}
+
+#[cfg(not(feature = "bytemuck"))]
+#[test]
+fn zeroed_requires_the_bytemuck_feature() {
+    let err = expand::derive(parse_quote! {
+        #[new(zeroed)]
+        struct Vertex {
+            pub id: u32,
+            weights: [u32; 300],
+        }
+    })
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`zeroed` requires the `bytemuck` feature of `impl-new-derive`"
+    );
+}
+
+#[cfg(not(feature = "regex"))]
+#[test]
+fn regex_requires_the_regex_feature() {
+    let err = expand::derive(parse_quote! {
+        struct Slug {
+            #[new(regex = "^[a-z]+$")]
+            pub value: String,
+        }
+    })
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`regex` requires the `regex` feature of `impl-new-derive`"
+    );
+}
+
+#[cfg(not(feature = "serde"))]
+#[test]
+fn from_json_requires_the_serde_feature() {
+    let err = expand::derive(parse_quote! {
+        #[new(from_json)]
+        struct ApiConfig {
+            pub endpoint: String,
+        }
+    })
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`from_json` requires the `serde` feature of `impl-new-derive`"
+    );
+}
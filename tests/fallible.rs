@@ -0,0 +1,356 @@
+use impl_new_derive::ImplNew;
+use std::num::ParseIntError;
+
+fn env_port(raw: &str) -> Result<u16, ParseIntError> {
+    raw.parse()
+}
+
+#[derive(ImplNew)]
+#[new(try_new)]
+struct Server {
+    pub host: String,
+    #[new(default(env_port("8080")?))]
+    port: u16,
+}
+
+#[test]
+fn try_new_evaluates_fallible_defaults() {
+    let server = Server::try_new("localhost".to_string()).unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+}
+
+#[allow(dead_code)]
+#[derive(ImplNew)]
+#[new(try_new, error = "ParseIntError")]
+struct Broken {
+    pub label: &'static str,
+    #[new(default("not a number".parse::<u32>()?))]
+    value: u32,
+}
+
+#[test]
+fn try_new_propagates_default_errors() {
+    let err = Broken::try_new("x").err().unwrap();
+    assert_eq!(err.to_string(), "invalid digit found in string");
+}
+
+#[derive(Debug, PartialEq)]
+enum LoadError {
+    Parse(String),
+    Io(String),
+}
+
+impl From<ParseIntError> for LoadError {
+    fn from(err: ParseIntError) -> Self {
+        LoadError::Parse(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(err: std::io::Error) -> Self {
+        LoadError::Io(err.kind().to_string())
+    }
+}
+
+#[derive(Debug, ImplNew)]
+#[new(try_new, error = "LoadError")]
+struct Loaded {
+    pub name: &'static str,
+    #[new(try)]
+    pub size: u32,
+    #[new(try)]
+    pub contents: Vec<u8>,
+}
+
+#[test]
+fn try_fields_unwrap_ok_values() {
+    let loaded =
+        Loaded::try_new("a", "12".parse::<u32>(), Ok::<_, std::io::Error>(vec![1])).unwrap();
+    assert_eq!(loaded.name, "a");
+    assert_eq!(loaded.size, 12);
+    assert_eq!(loaded.contents, [1]);
+}
+
+#[test]
+fn try_fields_short_circuit_on_the_first_error() {
+    let not_found = std::io::Error::from(std::io::ErrorKind::NotFound);
+    let err = Loaded::try_new("b", "x".parse::<u32>(), Err(not_found)).unwrap_err();
+    assert_eq!(
+        err,
+        LoadError::Parse("invalid digit found in string".to_string())
+    );
+
+    let not_found = std::io::Error::from(std::io::ErrorKind::NotFound);
+    let err = Loaded::try_new("c", Ok::<_, LoadError>(1), Err(not_found)).unwrap_err();
+    assert_eq!(err, LoadError::Io("entity not found".to_string()));
+}
+
+#[derive(Debug, ImplNew)]
+#[new(try_new)]
+struct Workers {
+    #[new(nonzero)]
+    pub count: std::num::NonZeroUsize,
+    pub name: &'static str,
+}
+
+#[test]
+fn nonzero_fields_are_checked_by_try_new() {
+    let workers = Workers::try_new(4, "io").unwrap();
+    assert_eq!(workers.count.get(), 4);
+    assert_eq!(workers.name, "io");
+
+    let err = Workers::try_new(0, "io").unwrap_err();
+    assert!(err.is::<std::num::TryFromIntError>());
+}
+
+#[derive(Debug, ImplNew)]
+#[new(try_new, error = "String", post = "value.validate()?")]
+struct Range {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Range {
+    fn validate(&self) -> Result<(), String> {
+        if self.start <= self.end {
+            Ok(())
+        } else {
+            Err(format!("{} > {}", self.start, self.end))
+        }
+    }
+}
+
+#[test]
+fn post_hook_can_reject_the_value_in_try_new() {
+    let range = Range::try_new(1, 3).unwrap();
+    assert_eq!((range.start, range.end), (1, 3));
+    assert_eq!(Range::try_new(3, 1).unwrap_err(), "3 > 1");
+}
+
+#[derive(ImplNew)]
+#[new(try_new, pinned)]
+struct PinnedTask {
+    #[new(try)]
+    pub id: u32,
+}
+
+#[test]
+fn pinned_try_new_returns_a_pinned_box_in_a_result() {
+    let task: Result<std::pin::Pin<Box<PinnedTask>>, _> = PinnedTask::try_new(Ok::<_, String>(3));
+    assert_eq!(task.unwrap().id, 3);
+}
+
+#[derive(Debug, PartialEq)]
+enum WindowError {
+    Inverted { min: u32, max: u32 },
+}
+
+fn validate_window(window: &Window) -> Result<(), WindowError> {
+    if window.min <= window.max {
+        Ok(())
+    } else {
+        Err(WindowError::Inverted {
+            min: window.min,
+            max: window.max,
+        })
+    }
+}
+
+#[derive(Debug, ImplNew)]
+#[new(try_new, error = "WindowError", validate_fn = "validate_window")]
+struct Window {
+    pub min: u32,
+    pub max: u32,
+}
+
+#[test]
+fn validate_fn_checks_invariants_across_fields() {
+    let window = Window::try_new(1, 5).unwrap();
+    assert_eq!((window.min, window.max), (1, 5));
+    assert_eq!(
+        Window::try_new(5, 1).unwrap_err(),
+        WindowError::Inverted { min: 5, max: 1 }
+    );
+}
+
+#[derive(Debug, ImplNew)]
+#[new(assert_invariants)]
+struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    fn invariants(&self) -> bool {
+        self.start <= self.end
+    }
+}
+
+#[test]
+fn assert_invariants_accepts_valid_values() {
+    let span = Span::new(2, 4);
+    assert_eq!((span.start, span.end), (2, 4));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "Span: `invariants` does not hold")]
+fn assert_invariants_panics_in_debug_builds() {
+    let _ = Span::new(4, 2);
+}
+
+#[derive(Debug, ImplNew)]
+#[new(assert_invariants = "is_sorted")]
+struct Sorted {
+    pub values: Vec<i32>,
+}
+
+impl Sorted {
+    fn is_sorted(&self) -> bool {
+        self.values.windows(2).all(|pair| pair[0] <= pair[1])
+    }
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "Sorted: `is_sorted` does not hold")]
+fn assert_invariants_calls_the_named_method() {
+    assert_eq!(Sorted::new(vec![1, 2]).values, [1, 2]);
+    let _ = Sorted::new(vec![2, 1]);
+}
+
+#[derive(Debug, ImplNew)]
+#[new(try_new)]
+struct Route {
+    pub name: &'static str,
+    #[new(non_empty)]
+    pub hops: std::collections::VecDeque<u16>,
+}
+
+#[test]
+fn non_empty_fields_are_checked_by_try_new() {
+    let route = Route::try_new("a", [1, 2].into()).unwrap();
+    assert_eq!((route.name, route.hops.len()), ("a", 2));
+
+    let err = Route::try_new("b", Default::default()).unwrap_err();
+    assert_eq!(err.to_string(), "Route: `hops` must not be empty");
+}
+
+#[derive(Debug, ImplNew)]
+#[new(try_new)]
+struct Endpoint {
+    pub host: &'static str,
+    #[new(param_ty = "&str", convert(port.parse()?))]
+    pub port: u16,
+}
+
+#[test]
+fn convert_expressions_may_use_the_question_mark() {
+    let endpoint = Endpoint::try_new("localhost", "8080").unwrap();
+    assert_eq!((endpoint.host, endpoint.port), ("localhost", 8080));
+    assert!(Endpoint::try_new("localhost", "http").is_err());
+}
+
+#[derive(Debug, ImplNew)]
+#[new(fallible)]
+struct Account {
+    pub owner: String,
+    #[new(default(0))]
+    balance: u64,
+}
+
+#[derive(Debug, ImplNew)]
+#[new(fallible, error = "std::num::ParseIntError")]
+struct Version {
+    #[new(default("1".parse()?))]
+    major: u32,
+    pub label: &'static str,
+}
+
+#[test]
+fn fallible_returns_a_result_from_new() {
+    let account: Result<Account, std::convert::Infallible> = Account::new("ada".to_string());
+    let Ok(account) = account;
+    assert_eq!((account.owner.as_str(), account.balance), ("ada", 0));
+
+    let version = Version::new("stable").unwrap();
+    assert_eq!((version.major, version.label), (1, "stable"));
+}
+
+#[derive(Debug, ImplNew)]
+#[new(from_map, with_overrides)]
+struct Queue {
+    #[new(non_empty)]
+    pub name: String,
+    pub capacity: usize,
+    served: u64,
+}
+
+#[test]
+fn from_map_returns_failed_checks_as_errors() {
+    let mut map = std::collections::HashMap::new();
+    map.insert("name".to_string(), "jobs".to_string());
+    map.insert("capacity".to_string(), "8".to_string());
+    let queue = Queue::from_map(&map).unwrap();
+    assert_eq!((queue.name.as_str(), queue.capacity), ("jobs", 8));
+
+    map.insert("name".to_string(), String::new());
+    let err = Queue::from_map(&map).unwrap_err();
+    assert_eq!(err.to_string(), "Queue: `name` must not be empty");
+}
+
+#[test]
+#[should_panic(expected = "Queue: `name` must not be empty")]
+fn with_overrides_checks_its_parameters() {
+    let mut base = Queue::new("jobs".to_string(), 8);
+    base.served = 3;
+    let queue = Queue::with(base, "mail".to_string(), 2);
+    assert_eq!((queue.name.as_str(), queue.served), ("mail", 3));
+    let base = Queue::new("jobs".to_string(), 8);
+    let _ = Queue::with(base, String::new(), 2);
+}
+
+#[derive(Debug, PartialEq)]
+struct EmptyRange;
+
+impl std::fmt::Display for EmptyRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the range is empty")
+    }
+}
+
+impl std::error::Error for EmptyRange {}
+
+fn validate_range(range: &Interval) -> Result<(), EmptyRange> {
+    if range.low < range.high {
+        Ok(())
+    } else {
+        Err(EmptyRange)
+    }
+}
+
+#[derive(Debug, ImplNew)]
+#[new(
+    try_new,
+    error = "EmptyRange",
+    validate_fn = "validate_range",
+    from_map
+)]
+struct Interval {
+    pub low: i32,
+    pub high: i32,
+}
+
+#[test]
+fn from_map_runs_validate_fn() {
+    let mut map = std::collections::HashMap::new();
+    map.insert("low".to_string(), "1".to_string());
+    map.insert("high".to_string(), "3".to_string());
+    assert_eq!(Interval::from_map(&map).unwrap().high, 3);
+
+    map.insert("high".to_string(), "0".to_string());
+    let err = Interval::from_map(&map).unwrap_err();
+    assert_eq!(err.to_string(), "the range is empty");
+    assert_eq!(Interval::try_new(2, 2).unwrap_err(), EmptyRange);
+}
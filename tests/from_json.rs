@@ -0,0 +1,30 @@
+use impl_new_derive::ImplNew;
+
+#[derive(Debug, ImplNew, serde::Deserialize)]
+#[new(from_json)]
+struct ApiConfig {
+    pub endpoint: String,
+    #[serde(default)]
+    #[new(default(3))]
+    retries: u8,
+}
+
+#[test]
+fn from_json_keeps_the_constructor() {
+    let config = ApiConfig::new("https://example.com".to_string());
+    assert_eq!(
+        (config.endpoint.as_str(), config.retries),
+        ("https://example.com", 3)
+    );
+}
+
+#[test]
+fn from_json_deserializes_the_struct() {
+    let config = ApiConfig::from_json(r#"{"endpoint": "https://example.com", "retries": 5}"#)
+        .expect("valid config");
+    assert_eq!(
+        (config.endpoint.as_str(), config.retries),
+        ("https://example.com", 5)
+    );
+    assert!(ApiConfig::from_json("{}").is_err());
+}
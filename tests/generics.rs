@@ -0,0 +1,383 @@
+use impl_new_derive::ImplNew;
+use std::fmt::Display;
+
+#[derive(ImplNew)]
+struct Holder<T: ?Sized> {
+    pub item: Box<T>,
+    uses: usize,
+}
+
+#[test]
+fn unsized_generic_bound_is_preserved() {
+    let text: Holder<str> = Holder::new("boxed".into());
+    assert_eq!(&*text.item, "boxed");
+    assert_eq!(text.uses, 0);
+
+    let shown: Holder<dyn Display> = Holder::new(Box::new(42));
+    assert_eq!(shown.item.to_string(), "42");
+
+    let slice: Holder<[u8]> = Holder::new(vec![1, 2, 3].into_boxed_slice());
+    assert_eq!(slice.item.len(), 3);
+}
+
+fn assert_send<T: Send>(value: T) -> T {
+    value
+}
+
+#[derive(ImplNew)]
+#[new(where = "T: Send + 'static, U: Clone")]
+struct Job<T, U> {
+    pub payload: T,
+    pub tag: U,
+}
+
+#[test]
+fn extra_where_predicates_constrain_the_constructor() {
+    let job = assert_send(Job::new(vec![1u8], String::from("tag")));
+    assert_eq!(job.payload, [1]);
+    assert_eq!(job.tag, "tag");
+}
+
+#[derive(ImplNew)]
+struct Grid<const W: usize, const H: usize> {
+    pub name: &'static str,
+    #[new(default([[0u8; W]; H]))]
+    cells: [[u8; W]; H],
+    #[new(default(W * H))]
+    area: usize,
+}
+
+#[test]
+fn const_generic_array_defaults_use_the_parameters() {
+    let grid = Grid::<40, 3>::new("wide");
+    assert_eq!(grid.name, "wide");
+    assert_eq!(grid.cells.len(), 3);
+    assert!(grid.cells.iter().all(|row| row == &[0; 40]));
+    assert_eq!(grid.area, 120);
+}
+
+#[derive(ImplNew)]
+#[new(generics_on_fn)]
+struct Cached<K, V> {
+    pub key: K,
+    entries: Vec<V>,
+}
+
+#[test]
+fn generics_on_fn_moves_private_only_parameters() {
+    let cached = Cached::new::<String>("user");
+    assert_eq!(cached.key, "user");
+    assert!(cached.entries.is_empty());
+
+    let inferred: Cached<u8, u64> = Cached::new(7);
+    assert_eq!(inferred.key, 7);
+    assert!(inferred.entries.is_empty());
+}
+
+#[derive(ImplNew)]
+struct Accumulator<T, U> {
+    pub label: &'static str,
+    total: T,
+    history: Vec<U>,
+}
+
+#[test]
+fn bare_generic_private_fields_get_a_default_bound() {
+    let acc = Accumulator::<u64, String>::new("sum");
+    assert_eq!(acc.label, "sum");
+    assert_eq!(acc.total, 0);
+    assert!(acc.history.is_empty());
+}
+
+#[derive(ImplNew)]
+struct Borrowed<'a, 'b, T: ?Sized> {
+    pub key: &'a str,
+    pub value: &'b T,
+    #[new(group = "span")]
+    pub start: &'a [u8],
+    #[new(group = "span")]
+    pub end: &'b [u8],
+}
+
+fn key_of<'a>(source: &'a str, value: &u32) -> &'a str {
+    let borrowed = Borrowed::new(source, value, (source.as_bytes(), &[]));
+    borrowed.key
+}
+
+#[test]
+fn reference_fields_keep_their_distinct_lifetimes() {
+    let source = String::from("answer");
+    let key = {
+        let value = 42;
+        key_of(&source, &value)
+    };
+    assert_eq!(key, "answer");
+
+    let slice: &[u8] = &[1, 2];
+    let unsized_value = Borrowed::new("k", slice, (&[0][..], &[9][..]));
+    assert_eq!(unsized_value.value, [1, 2]);
+    assert_eq!(
+        (unsized_value.start, unsized_value.end),
+        (&[0][..], &[9][..])
+    );
+}
+
+#[derive(ImplNew)]
+struct Conn<const OPEN: bool> {
+    pub address: &'static str,
+    attempts: u8,
+}
+
+impl Conn<false> {
+    fn open(self) -> Conn<true> {
+        Conn {
+            address: self.address,
+            attempts: self.attempts + 1,
+        }
+    }
+}
+
+impl Conn<true> {
+    fn is_open(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn const_bool_typestates_share_the_generic_constructor() {
+    let closed = Conn::<false>::new("db:5432");
+    let open = closed.open();
+    assert!(open.is_open());
+    assert_eq!((open.address, open.attempts), ("db:5432", 1));
+
+    let already_open = Conn::<true>::new("cache:6379");
+    assert_eq!(already_open.attempts, 0);
+}
+
+#[derive(ImplNew)]
+struct Recorder<const WITH_LOG: bool> {
+    pub data: Vec<u8>,
+    #[new(default(if WITH_LOG { Vec::with_capacity(16) } else { Vec::new() }))]
+    log: Vec<String>,
+}
+
+#[test]
+fn defaults_can_branch_on_a_const_bool_parameter() {
+    let logging = Recorder::<true>::new(vec![1, 2]);
+    let quiet = Recorder::<false>::new(vec![3]);
+    assert_eq!(logging.data, [1, 2]);
+    assert!(logging.log.is_empty() && logging.log.capacity() >= 16);
+    assert_eq!(quiet.data, [3]);
+    assert_eq!(quiet.log.capacity(), 0);
+}
+
+mod shapes {
+    #[derive(Debug, Default, PartialEq)]
+    pub struct T(pub u8);
+}
+
+#[derive(ImplNew)]
+struct Shelf<T> {
+    pub item: T,
+    #[new(default(shapes::T(7)))]
+    shape: shapes::T,
+    #[new(default({
+        fn make<T: Default>() -> T {
+            T::default()
+        }
+        make::<Vec<T>>()
+    }))]
+    spare: Vec<T>,
+    #[new(default(|value: T| -> Option<T> { Some(value) }))]
+    wrap: fn(T) -> Option<T>,
+}
+
+#[test]
+fn defaults_with_their_own_t_do_not_capture_the_struct_parameter() {
+    let holder = Shelf::new("item");
+    assert_eq!(holder.item, "item");
+    assert_eq!(holder.shape, shapes::T(7));
+    assert!(holder.spare.is_empty());
+    assert_eq!((holder.wrap)("x"), Some("x"));
+}
+
+#[derive(ImplNew)]
+struct Pair<A, B> {
+    pub left: A,
+    pub right: B,
+}
+
+#[derive(ImplNew)]
+struct Tagged<T, Tag> {
+    pub value: T,
+    tags: Vec<Tag>,
+}
+
+#[test]
+fn impl_generics_are_inferred_from_arguments_or_named_on_the_type() {
+    let inferred = Pair::new(1u8, "a");
+    assert_eq!((inferred.left, inferred.right), (1, "a"));
+    let turbofish = Pair::<u16, char>::new(2, 'b');
+    assert_eq!((turbofish.left, turbofish.right), (2, 'b'));
+
+    // `Tag` only appears in a defaulted field, so it is named explicitly or by annotation.
+    let named = Tagged::<_, String>::new(3);
+    assert_eq!((named.value, named.tags.len()), (3, 0));
+    let annotated: Tagged<i32, u8> = Tagged::new(4);
+    assert_eq!((annotated.value, annotated.tags.len()), (4, 0));
+}
+
+struct User;
+
+#[derive(ImplNew)]
+struct Id<T> {
+    pub raw: u64,
+    marker: std::marker::PhantomData<T>,
+}
+
+#[derive(ImplNew)]
+struct Handle<'a, T: ?Sized> {
+    pub slot: u32,
+    owner: std::marker::PhantomData<&'a T>,
+}
+
+#[test]
+fn phantom_only_generics_stay_on_the_impl_without_bounds() {
+    // `User` implements no traits; the `PhantomData` field adds no `Default` bound.
+    let id = Id::<User>::new(7);
+    assert_eq!(id.raw, 7);
+    let annotated: Id<User> = Id::new(8);
+    assert_eq!(annotated.raw, 8);
+
+    let handle = Handle::<str>::new(1);
+    assert_eq!(handle.slot, 1);
+}
+
+#[derive(ImplNew)]
+struct Channel<T>
+where
+    T: Clone,
+    T: Send,
+{
+    pub value: T,
+    backlog: Vec<T>,
+}
+
+fn assert_send_clone<T: Clone + Send>(value: T) -> T {
+    value.clone()
+}
+
+#[test]
+fn multiple_predicates_on_one_parameter_are_preserved() {
+    let channel = Channel::new(String::from("msg"));
+    assert_eq!(assert_send_clone(channel.value), "msg");
+    assert!(channel.backlog.is_empty());
+}
+
+#[derive(ImplNew)]
+struct Blob<T: ?Sized = str> {
+    pub data: Box<T>,
+    reads: usize,
+}
+
+#[derive(ImplNew)]
+#[new(typed_builder)]
+struct Chunk<T: ?Sized + core::fmt::Debug = [u8]> {
+    pub offset: usize,
+    pub data: Box<T>,
+}
+
+#[derive(ImplNew)]
+#[new(checked_builder)]
+struct Slot<T: ?Sized = str> {
+    pub data: Box<T>,
+}
+
+#[test]
+fn defaulted_unsized_parameters_keep_their_bounds_and_defaults() {
+    let text: Blob = Blob::new("text".into());
+    assert_eq!((&*text.data, text.reads), ("text", 0));
+    let number = Blob::<u32>::new(Box::new(7));
+    assert_eq!(*number.data, 7);
+
+    let chunk: Chunk = Chunk::builder().offset(4).data(vec![1, 2].into()).build();
+    assert_eq!((chunk.offset, &*chunk.data), (4, &[1u8, 2][..]));
+
+    let slot: Slot = Slot::builder().data("slot".into()).build().unwrap();
+    assert_eq!(&*slot.data, "slot");
+}
+
+trait Config: Default {
+    fn name(&self) -> &'static str;
+}
+
+#[derive(Default)]
+struct Defaults;
+
+impl Config for Defaults {
+    fn name(&self) -> &'static str {
+        "defaults"
+    }
+}
+
+#[derive(ImplNew)]
+struct Service<T: Config> {
+    pub port: u16,
+    #[new(skip_default_bound)]
+    config: T,
+}
+
+#[test]
+fn skip_default_bound_relies_on_the_supertrait() {
+    let service = Service::<Defaults>::new(80);
+    assert_eq!((service.port, service.config.name()), (80, "defaults"));
+}
+
+#[derive(ImplNew)]
+#[new(
+    also_impl_for = "Bytes = Buffer<u8>",
+    also_impl_for = "Words = Buffer<u16>"
+)]
+struct Buffer<T> {
+    pub capacity: usize,
+    items: Vec<T>,
+}
+
+#[test]
+fn also_impl_for_names_concrete_instantiations() {
+    let bytes = Bytes::new(16);
+    let _: &Buffer<u8> = &bytes;
+    assert_eq!((bytes.capacity, bytes.items.len()), (16, 0));
+    let words: Buffer<u16> = Words::new(4);
+    assert_eq!(words.capacity, 4);
+}
+
+#[derive(ImplNew)]
+struct Task<T>
+where
+    Self: Send,
+{
+    pub payload: T,
+    attempts: u8,
+}
+
+#[derive(ImplNew)]
+#[new(free_fn = "make_message", typed_builder)]
+struct Message<T: PartialEq<T>>
+where
+    Self: Send + Sync,
+{
+    pub body: T,
+    pub retries: Vec<T>,
+}
+
+#[test]
+fn where_predicates_on_self_name_the_struct() {
+    let task = Task::new(vec![1u8]);
+    assert_eq!((task.payload, task.attempts), (vec![1], 0));
+
+    let message = make_message("hi", vec!["hello"]);
+    assert_eq!((message.body, message.retries), ("hi", vec!["hello"]));
+    let built = Message::builder().retries(Vec::new()).body(7).build();
+    assert_eq!(built.body, 7);
+}
@@ -0,0 +1,264 @@
+use impl_new_derive::ImplNew;
+use typed_builder::TypedBuilder;
+
+#[derive(ImplNew, TypedBuilder)]
+struct Settings {
+    pub name: String,
+    #[builder(default = 8)]
+    pub workers: usize,
+    #[builder(default = Vec::new())]
+    #[new(default(vec!["localhost".to_string()]))]
+    hosts: Vec<String>,
+}
+
+#[test]
+fn composes_with_typed_builder() {
+    let built = Settings::builder().name("built".to_string()).build();
+    assert_eq!(built.workers, 8);
+    assert!(built.hosts.is_empty());
+
+    let constructed = Settings::new("new".to_string(), 2);
+    assert_eq!(constructed.name, "new");
+    assert_eq!(constructed.workers, 2);
+    assert_eq!(constructed.hosts, ["localhost"]);
+    assert_eq!(built.name, "built");
+}
+
+#[derive(Debug, ImplNew, serde::Serialize, serde::Deserialize)]
+struct Record {
+    #[serde(default)]
+    pub id: u32,
+    #[serde(skip)]
+    pub cache: Vec<u8>,
+    #[serde(rename = "label")]
+    #[new(default("unnamed".to_string()))]
+    pub name: String,
+    #[serde(skip)]
+    revision: u8,
+}
+
+#[test]
+fn external_attributes_do_not_change_parameters() {
+    let record = Record::new(7, vec![1]);
+    assert_eq!(record.id, 7);
+    assert_eq!(record.cache, [1]);
+    assert_eq!(record.name, "unnamed");
+    assert_eq!(record.revision, 0);
+}
+
+/// Only `new`, `impl_new` and `default` attributes are interpreted; everything else on a field is
+/// left to the compiler and the other derives.
+#[derive(Debug, ImplNew, TypedBuilder, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Annotated {
+    /// Documented, renamed for serde and defaulted for the builder.
+    #[serde(rename = "ID", alias = "identifier")]
+    #[builder(setter(into))]
+    #[allow(unused)]
+    pub id: String,
+    #[rustfmt::skip]
+    #[cfg_attr(any(), serde(skip))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[builder(default)]
+    #[doc(hidden)]
+    pub tags: Vec<String>,
+    #[cfg_attr(all(), serde(skip))]
+    #[builder(default = 9)]
+    #[expect(dead_code)]
+    #[new(default(9))]
+    weight: u8,
+}
+
+#[test]
+fn unrelated_field_attributes_are_ignored() {
+    let annotated = Annotated::new("a".to_string(), vec!["x".to_string()]);
+    assert_eq!(annotated.id, "a");
+    assert_eq!(annotated.tags, ["x"]);
+    let built = Annotated::builder().id("b").build();
+    assert!(built.tags.is_empty());
+}
+
+#[derive(Debug, ImplNew)]
+#[new(from_map)]
+struct Listener {
+    pub host: String,
+    pub port: u16,
+    #[new(default(64))]
+    backlog: u32,
+}
+
+#[test]
+fn from_map_parses_parameters_by_field_name() {
+    let mut map = std::collections::HashMap::new();
+    map.insert("host".to_string(), "0.0.0.0".to_string());
+    map.insert("port".to_string(), "8080".to_string());
+    let listener = Listener::from_map(&map).unwrap();
+    assert_eq!(listener.host, "0.0.0.0");
+    assert_eq!(listener.port, 8080);
+    assert_eq!(listener.backlog, 64);
+
+    map.insert("port".to_string(), "http".to_string());
+    let err = Listener::from_map(&map).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Listener: `port` is invalid: invalid digit found in string"
+    );
+
+    map.remove("host");
+    let err = Listener::from_map(&map).unwrap_err();
+    assert_eq!(err.to_string(), "Listener: `host` is missing from the map");
+}
+
+#[derive(ImplNew)]
+#[new(from_map)]
+struct Tile {
+    pub map: String,
+    pub zoom: u8,
+}
+
+#[test]
+fn from_map_argument_is_not_shadowed_by_a_map_field() {
+    let entries = [("map", "world"), ("zoom", "3")]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let tile = Tile::from_map(&entries).unwrap();
+    assert_eq!((tile.map.as_str(), tile.zoom), ("world", 3));
+}
+
+#[derive(ImplNew)]
+#[new(from_map, optional_params)]
+struct Pool {
+    pub name: String,
+    #[new(default(4))]
+    pub size: usize,
+}
+
+#[test]
+fn from_map_falls_back_to_defaults_of_optional_params() {
+    let mut map = std::collections::HashMap::new();
+    map.insert("name".to_string(), "db".to_string());
+    let pool = Pool::from_map(&map).unwrap();
+    assert_eq!((pool.name.as_str(), pool.size), ("db", 4));
+
+    map.insert("size".to_string(), "16".to_string());
+    assert_eq!(Pool::from_map(&map).unwrap().size, 16);
+}
+
+#[derive(ImplNew, Debug, PartialEq)]
+#[new(from)]
+#[repr(transparent)]
+struct Meters {
+    pub value: f64,
+}
+
+fn total(lengths: impl IntoIterator<Item = impl Into<Meters>>) -> f64 {
+    lengths.into_iter().map(|length| length.into().value).sum()
+}
+
+#[derive(ImplNew)]
+#[new(from)]
+struct Tagged<T> {
+    pub inner: T,
+    hits: u32,
+}
+
+#[test]
+fn from_wraps_the_single_parameter() {
+    assert_eq!(Meters::from(1.5), Meters::new(1.5));
+    let meters: Meters = 2.0.into();
+    assert_eq!(meters.value, 2.0);
+    assert_eq!(total([1.0, 2.5]), 3.5);
+
+    let tagged: Tagged<&str> = "id".into();
+    assert_eq!((tagged.inner, tagged.hits), ("id", 0));
+}
+
+mod framework {
+    pub trait Constructible {}
+
+    pub fn constructible<T: Constructible + ?Sized>() -> bool {
+        true
+    }
+}
+
+trait Audited {}
+
+// A trait from another crate, implemented for the local struct.
+#[derive(ImplNew)]
+#[new(marker = "std::panic::RefUnwindSafe")]
+struct Snapshot<T> {
+    pub data: Vec<T>,
+}
+
+#[test]
+fn marker_traits_may_come_from_other_crates() {
+    fn unwind_safe<T: std::panic::RefUnwindSafe>(_: &T) {}
+
+    let snapshot = Snapshot::new(vec![1u8]);
+    unwind_safe(&snapshot);
+    assert_eq!(snapshot.data, [1]);
+}
+
+#[derive(Debug, ImplNew)]
+#[new(singleton)]
+struct GlobalConfig {
+    #[new(default(std::sync::atomic::AtomicU32::new(1)))]
+    generation: std::sync::atomic::AtomicU32,
+    #[new(default("prod"))]
+    environment: &'static str,
+}
+
+#[test]
+fn singleton_shares_one_instance() {
+    use std::sync::atomic::Ordering;
+
+    let config = GlobalConfig::instance();
+    assert_eq!(config.environment, "prod");
+    config.generation.fetch_add(1, Ordering::SeqCst);
+    assert!(std::ptr::eq(config, GlobalConfig::instance()));
+    assert!(GlobalConfig::instance().generation.load(Ordering::SeqCst) >= 2);
+    assert_eq!(GlobalConfig::new().generation.load(Ordering::SeqCst), 1);
+}
+
+#[derive(ImplNew)]
+#[new(deref)]
+struct Names {
+    pub inner: Vec<String>,
+}
+
+#[derive(ImplNew)]
+#[new(deref, from)]
+struct Label<T: AsRef<str>> {
+    pub text: T,
+}
+
+#[test]
+fn deref_exposes_the_wrapped_field() {
+    let mut names = Names::new(vec!["ada".to_string()]);
+    names.push("grace".to_string());
+    assert_eq!(names.len(), 2);
+    assert_eq!(names.inner, ["ada", "grace"]);
+
+    let label: Label<&str> = "Title".into();
+    assert_eq!(label.to_lowercase(), "title");
+}
+
+#[derive(ImplNew)]
+#[new(marker = "framework::Constructible", marker = "Audited")]
+struct Invoice<'a, T: Clone, const N: usize> {
+    pub lines: [&'a T; N],
+    total: u64,
+}
+
+#[test]
+fn marker_traits_are_implemented_for_every_instantiation() {
+    fn audited<T: Audited>(_: &T) {}
+
+    assert!(framework::constructible::<Invoice<'static, String, 2>>());
+    let amount = 5u8;
+    let invoice = Invoice::new([&amount; 3]);
+    audited(&invoice);
+    assert_eq!(invoice.total, 0);
+    assert_eq!(*invoice.lines[0], 5);
+}
@@ -0,0 +1,83 @@
+#![allow(deprecated)]
+
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Legacy {
+    pub name: &'static str,
+    #[default(42)]
+    answer: u32,
+}
+
+#[test]
+fn legacy_default_attribute_still_applies() {
+    let legacy = Legacy::new("old");
+    assert_eq!(legacy.name, "old");
+    assert_eq!(legacy.answer, 42);
+}
+
+#[derive(ImplNew)]
+struct LegacyBuild {
+    #[default(env!("CARGO_PKG_NAME"))]
+    package: &'static str,
+    #[default(concat!("a", "b", 1))]
+    tag: &'static str,
+}
+
+#[test]
+fn legacy_default_accepts_macro_calls() {
+    let build = LegacyBuild::new();
+    assert_eq!(build.package, "impl-new-derive");
+    assert_eq!(build.tag, "ab1");
+}
+
+#[derive(ImplNew)]
+struct LegacyChain {
+    #[default(std::env::var("IMPL_NEW_UNSET_VARIABLE").ok().and_then(|s| s.parse().ok()).unwrap_or(42))]
+    threads: u32,
+    #[default(vec![1u8, 2, 3].into_iter().map(|n| n * 2).collect::<Vec<_>>())]
+    doubled: Vec<u8>,
+}
+
+#[test]
+fn legacy_default_accepts_method_chains() {
+    let chain = LegacyChain::new();
+    assert_eq!(chain.threads, 42);
+    assert_eq!(chain.doubled, [2, 4, 6]);
+}
+
+#[derive(ImplNew)]
+struct LegacySampler {
+    #[default(if cfg!(debug_assertions) { 100 } else { 1000 })]
+    interval_ms: u64,
+}
+
+#[test]
+fn legacy_default_accepts_cfg_macro_conditions() {
+    let expected = if cfg!(debug_assertions) { 100 } else { 1000 };
+    assert_eq!(LegacySampler::new().interval_ms, expected);
+}
+
+#[derive(ImplNew)]
+struct LegacyUnit {
+    pub id: u8,
+    #[default(())]
+    marker: (),
+}
+
+#[test]
+fn legacy_default_accepts_unit() {
+    let unit = LegacyUnit::new(1);
+    assert_eq!((unit.id, unit.marker), (1, ()));
+}
+
+#[derive(ImplNew)]
+struct LegacyEmbedded {
+    #[default(include_bytes!("fixtures/magic.bin"))]
+    magic: &'static [u8],
+}
+
+#[test]
+fn legacy_default_accepts_include_bytes() {
+    assert_eq!(LegacyEmbedded::new().magic.len(), 6);
+}
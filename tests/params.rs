@@ -0,0 +1,510 @@
+use impl_new_derive::ImplNew;
+
+#[derive(Debug, PartialEq)]
+enum Color {
+    Red,
+}
+
+#[derive(ImplNew)]
+struct Sprite {
+    #[new(group = "pos")]
+    pub x: f32,
+    #[new(group = "pos")]
+    pub y: f32,
+    pub color: Color,
+    frame: u32,
+}
+
+#[test]
+fn grouped_fields_form_one_tuple_parameter() {
+    let sprite = Sprite::new((1.5, -2.0), Color::Red);
+    assert_eq!(sprite.x, 1.5);
+    assert_eq!(sprite.y, -2.0);
+    assert_eq!(sprite.color, Color::Red);
+    assert_eq!(sprite.frame, 0);
+}
+
+#[derive(ImplNew)]
+struct Playlist {
+    pub name: &'static str,
+    #[new(from_iter)]
+    pub tracks: Vec<u32>,
+    #[new(from_iter)]
+    pub tags: std::collections::BTreeSet<&'static str>,
+}
+
+#[test]
+fn from_iter_collects_arrays_and_iterators() {
+    let playlist = Playlist::new("mix", [3, 1, 2], ["b", "a", "b"]);
+    assert_eq!(playlist.name, "mix");
+    assert_eq!(playlist.tracks, vec![3, 1, 2]);
+    assert_eq!(playlist.tags.into_iter().collect::<Vec<_>>(), ["a", "b"]);
+
+    let evens = Playlist::new("evens", (0..6).filter(|n| n % 2 == 0), []);
+    assert_eq!(evens.tracks, vec![0, 2, 4]);
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Metric;
+
+#[derive(ImplNew)]
+struct Gauge<U> {
+    pub value: f64,
+    #[new(skip_if_default)]
+    pub unit: Metric,
+    pub marker: std::marker::PhantomData<U>,
+}
+
+#[test]
+fn zero_sized_fields_are_not_parameters() {
+    let gauge = Gauge::<u8>::new(0.5);
+    assert_eq!(gauge.value, 0.5);
+    assert_eq!(gauge.unit, Metric);
+    assert_eq!(gauge.marker, std::marker::PhantomData);
+}
+
+#[derive(ImplNew)]
+struct SharedBuffer {
+    #[new(lock)]
+    pub bytes: std::sync::Mutex<Vec<u8>>,
+    #[new(lock)]
+    pub readers: std::sync::RwLock<usize>,
+}
+
+#[test]
+fn lock_fields_take_the_inner_value() {
+    let buffer = SharedBuffer::new(vec![1, 2, 3], 2);
+    buffer.bytes.lock().unwrap().push(4);
+    assert_eq!(*buffer.bytes.lock().unwrap(), [1, 2, 3, 4]);
+    assert_eq!(*buffer.readers.read().unwrap(), 2);
+}
+
+#[derive(ImplNew)]
+struct Pool {
+    #[new(nonzero)]
+    pub size: std::num::NonZeroU32,
+    #[new(nonzero)]
+    pub offset: std::num::NonZero<i64>,
+}
+
+#[test]
+fn nonzero_fields_take_the_raw_integer() {
+    let pool = Pool::new(8, -1);
+    assert_eq!(pool.size.get(), 8);
+    assert_eq!(pool.offset.get(), -1);
+}
+
+#[test]
+#[should_panic(expected = "Pool: `size` must be non-zero")]
+fn nonzero_fields_panic_on_zero() {
+    let _ = Pool::new(0, 1);
+}
+
+#[derive(ImplNew)]
+struct Backoff {
+    #[new(from_millis)]
+    pub delay: std::time::Duration,
+    #[new(from_secs)]
+    pub deadline: core::time::Duration,
+    #[new(from_micros)]
+    pub jitter: std::time::Duration,
+}
+
+#[test]
+fn duration_fields_take_a_count_of_units() {
+    let backoff = Backoff::new(250, 30, 1_500);
+    assert_eq!(backoff.delay, std::time::Duration::from_millis(250));
+    assert_eq!(backoff.deadline, std::time::Duration::from_secs(30));
+    assert_eq!(backoff.jitter, std::time::Duration::from_micros(1_500));
+}
+
+#[derive(ImplNew)]
+struct Volume {
+    #[new(clamp = "0..=100")]
+    pub level: u8,
+    #[new(clamp = "-1.0..=1.0")]
+    pub balance: f32,
+    #[new(clamp = "1..")]
+    pub channels: u16,
+}
+
+#[test]
+fn clamp_normalizes_out_of_range_parameters() {
+    let volume = Volume::new(150, -3.5, 0);
+    assert_eq!(
+        (volume.level, volume.balance, volume.channels),
+        (100, -1.0, 1)
+    );
+    let volume = Volume::new(40, 0.25, 6);
+    assert_eq!(
+        (volume.level, volume.balance, volume.channels),
+        (40, 0.25, 6)
+    );
+}
+
+#[derive(ImplNew)]
+struct Frame {
+    #[new(unbox)]
+    pub pixels: [u8; 4],
+    #[new(unbox)]
+    pub label: String,
+}
+
+#[test]
+fn unbox_fields_move_the_value_out_of_the_box() {
+    let pixels = Box::new([1, 2, 3, 4]);
+    let frame = Frame::new(pixels, Box::new("front".to_string()));
+    assert_eq!(frame.pixels, [1, 2, 3, 4]);
+    assert_eq!(frame.label, "front");
+}
+
+#[derive(ImplNew)]
+struct Session {
+    pub user: &'static str,
+    #[new(init)]
+    token: String,
+    #[new(init = "make_started")]
+    started: u64,
+}
+
+#[test]
+fn init_fields_are_built_by_closure_parameters() {
+    let clock = 1_700_000_000;
+    let session = Session::new("ada", || format!("{}-token", "ada"), move || clock);
+    assert_eq!(session.user, "ada");
+    assert_eq!(session.token, "ada-token");
+    assert_eq!(session.started, clock);
+}
+
+#[derive(ImplNew)]
+struct Scratch {
+    #[new(cell)]
+    pub buffer: std::cell::RefCell<Vec<u8>>,
+    #[new(cell)]
+    pub hits: std::cell::Cell<u32>,
+}
+
+#[test]
+fn cell_fields_take_the_inner_value() {
+    let scratch = Scratch::new(vec![7, 8], 1);
+    scratch.buffer.borrow_mut().push(9);
+    scratch.hits.set(scratch.hits.get() + 1);
+    assert_eq!(*scratch.buffer.borrow(), [7, 8, 9]);
+    assert_eq!(scratch.hits.get(), 2);
+}
+
+#[derive(ImplNew)]
+struct SelfRef {
+    pub data: String,
+    pub pin: std::marker::PhantomPinned,
+}
+
+/// Resolves only for types that are not `Unpin`; for `Unpin` types both impls apply and the
+/// call becomes ambiguous.
+trait NotUnpin<A> {
+    fn check() {}
+}
+impl<T: ?Sized> NotUnpin<()> for T {}
+impl<T: ?Sized + Unpin> NotUnpin<u8> for T {}
+
+#[test]
+fn phantom_pinned_fields_are_not_parameters() {
+    <SelfRef as NotUnpin<_>>::check();
+
+    let value = Box::pin(SelfRef::new("owned".to_string()));
+    assert_eq!(value.data, "owned");
+    let _ = &value.pin;
+}
+
+#[derive(ImplNew)]
+#[new(str_params)]
+struct Contact {
+    pub name: String,
+    pub email: std::string::String,
+    #[new(no_str)]
+    pub notes: String,
+    pub age: u8,
+}
+
+#[test]
+fn str_params_take_string_fields_as_str() {
+    let contact = Contact::new("Ada", "ada@example.org", String::from("met at the lab"), 36);
+    assert_eq!(contact.name, "Ada");
+    assert_eq!(contact.email, "ada@example.org");
+    assert_eq!(contact.notes, "met at the lab");
+    assert_eq!(contact.age, 36);
+}
+
+#[derive(Debug, PartialEq, ImplNew)]
+struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(ImplNew)]
+#[new(macro)]
+struct Marker {
+    pub label: &'static str,
+    #[new(flatten(x: f32, y: f32))]
+    pub position: Position,
+    visible: bool,
+}
+
+#[test]
+fn flatten_passes_inner_parameters_through() {
+    let marker = Marker::new("home", 1.0, 2.5);
+    assert_eq!(marker.label, "home");
+    assert_eq!(marker.position, Position::new(1.0, 2.5));
+    assert!(!marker.visible);
+
+    let marker = marker! { y: 4.0, label: "work", x: 3.0 };
+    assert_eq!(marker.position, Position { x: 3.0, y: 4.0 });
+}
+
+#[derive(ImplNew)]
+#[new(sorted_params)]
+struct Sorted {
+    pub zone: &'static str,
+    pub amount: u32,
+    hidden: bool,
+    pub r#match: char,
+    pub count: i8,
+}
+
+#[test]
+fn sorted_params_orders_parameters_by_field_name() {
+    let sorted = Sorted::new(5, -1, 'm', "utc");
+    assert_eq!(sorted.amount, 5);
+    assert_eq!(sorted.count, -1);
+    assert_eq!(sorted.r#match, 'm');
+    assert_eq!(sorted.zone, "utc");
+    assert!(!sorted.hidden);
+}
+
+#[derive(ImplNew)]
+#[new(as_tuple_args, macro)]
+struct Pixel {
+    pub x: u16,
+    pub y: u16,
+    pub color: &'static str,
+    alpha: u8,
+}
+
+fn forward<A, T>(args: A, ctor: impl FnOnce(A) -> T) -> T {
+    ctor(args)
+}
+
+#[test]
+fn as_tuple_args_takes_one_tuple_parameter() {
+    let pixel = forward((3, 4, "red"), Pixel::new);
+    assert_eq!(
+        (pixel.x, pixel.y, pixel.color, pixel.alpha),
+        (3, 4, "red", 0)
+    );
+
+    let pixel = pixel! { color: "blue", y: 2, x: 1 };
+    assert_eq!((pixel.x, pixel.y, pixel.color), (1, 2, "blue"));
+}
+
+#[derive(ImplNew)]
+#[new(optional_params, with_overrides)]
+struct Retry {
+    pub name: &'static str,
+    #[new(default(3))]
+    pub attempts: u8,
+    #[new(default)]
+    pub backoff_ms: u64,
+    #[new(default(name.len()))]
+    label_len: usize,
+}
+
+#[test]
+fn optional_params_take_defaulted_public_fields_as_options() {
+    let retry = Retry::new("fetch", None, Some(250));
+    assert_eq!(retry.name, "fetch");
+    assert_eq!((retry.attempts, retry.backoff_ms), (3, 250));
+    assert_eq!(retry.label_len, 5);
+
+    let retry = Retry::new("put", Some(1), None);
+    assert_eq!((retry.attempts, retry.backoff_ms), (1, 0));
+
+    let updated = Retry::with(retry, "post", None, Some(10));
+    assert_eq!(
+        (updated.name, updated.attempts, updated.backoff_ms),
+        ("post", 1, 10)
+    );
+}
+
+#[derive(ImplNew)]
+struct Index {
+    #[new(collect)]
+    pub words: Vec<String>,
+    #[new(collect)]
+    pub unique: std::collections::HashSet<u8>,
+    #[new(collect)]
+    counts: std::collections::HashMap<&'static str, usize>,
+    #[new(collect)]
+    pub ordered: std::collections::BTreeMap<u8, char>,
+}
+
+#[test]
+fn collect_builds_sets_and_maps_from_iterators() {
+    let index = Index::new(
+        ["a".to_string()],
+        [1, 1, 2],
+        [("a", 1), ("b", 2)],
+        (0..3).zip(['x', 'y', 'z']),
+    );
+    assert_eq!(index.words, ["a"]);
+    assert_eq!(index.unique.len(), 2);
+    assert_eq!(index.counts["b"], 2);
+    assert_eq!(index.ordered.values().collect::<String>(), "xyz");
+}
+
+#[derive(ImplNew)]
+struct Batch {
+    #[new(non_empty)]
+    pub jobs: Vec<u32>,
+    #[new(non_empty)]
+    pub label: String,
+}
+
+#[test]
+fn non_empty_accepts_filled_collections() {
+    let batch = Batch::new(vec![1], "nightly".to_string());
+    assert_eq!((batch.jobs, batch.label.as_str()), (vec![1], "nightly"));
+}
+
+#[test]
+#[should_panic(expected = "Batch: `jobs` must not be empty")]
+fn non_empty_panics_on_an_empty_collection() {
+    let _ = Batch::new(Vec::new(), "nightly".to_string());
+}
+
+#[derive(Default)]
+struct Arena {
+    slots: Vec<String>,
+}
+
+impl Arena {
+    fn alloc(&mut self, value: &str) -> usize {
+        self.slots.push(value.to_string());
+        self.slots.len() - 1
+    }
+}
+
+#[derive(ImplNew)]
+#[new(resource(arena: &mut Arena))]
+struct Node {
+    pub label: &'static str,
+    #[new(default(arena.alloc(label)))]
+    slot: usize,
+    #[new(default(arena.alloc("meta")))]
+    meta_slot: usize,
+    children: Vec<usize>,
+}
+
+#[test]
+fn resource_parameters_come_first_and_feed_the_defaults() {
+    let mut arena = Arena::default();
+    let root = Node::new(&mut arena, "root");
+    let leaf = Node::new(&mut arena, "leaf");
+    assert_eq!((root.label, root.slot, root.meta_slot), ("root", 0, 1));
+    assert_eq!((leaf.slot, leaf.meta_slot), (2, 3));
+    assert!(root.children.is_empty());
+    assert_eq!(arena.slots, ["root", "meta", "leaf", "meta"]);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Symbol(usize);
+
+#[derive(Default)]
+struct Interner {
+    names: Vec<String>,
+}
+
+impl Interner {
+    fn intern(&mut self, name: &str) -> Symbol {
+        match self.names.iter().position(|known| known == name) {
+            Some(index) => Symbol(index),
+            None => {
+                self.names.push(name.to_string());
+                Symbol(self.names.len() - 1)
+            }
+        }
+    }
+}
+
+#[derive(ImplNew)]
+#[new(resource(interner: &mut Interner))]
+struct Binding {
+    #[new(intern = "interner")]
+    pub name: Symbol,
+    #[new(intern = "interner")]
+    pub ty: Symbol,
+    pub mutable: bool,
+}
+
+#[test]
+fn intern_fields_take_a_str_and_use_the_resource() {
+    let mut interner = Interner::default();
+    let x = Binding::new(&mut interner, "x", "i32", false);
+    let y = Binding::new(&mut interner, "y", "i32", true);
+    assert_eq!((x.name, x.ty, x.mutable), (Symbol(0), Symbol(1), false));
+    assert_eq!((y.name, y.ty, y.mutable), (Symbol(2), Symbol(1), true));
+    assert_eq!(interner.names, ["x", "i32", "y"]);
+}
+
+#[derive(ImplNew)]
+struct Asset {
+    #[new(param_ty = "std::path::PathBuf", convert(std::sync::Arc::from(path)))]
+    pub path: std::sync::Arc<std::path::Path>,
+    #[new(param_ty = "&str")]
+    pub title: String,
+    #[new(convert(tag.to_lowercase()))]
+    pub tag: String,
+}
+
+#[test]
+fn param_ty_and_convert_store_a_converted_parameter() {
+    let asset = Asset::new(
+        std::path::PathBuf::from("assets/logo.png"),
+        "Logo",
+        "PNG".to_string(),
+    );
+    assert_eq!(&*asset.path, std::path::Path::new("assets/logo.png"));
+    assert_eq!(asset.title, "Logo");
+    assert_eq!(asset.tag, "png");
+}
+
+#[derive(ImplNew)]
+struct Lease {
+    #[new(rename = "TTL", param_attr = "allow(non_snake_case)")]
+    pub ttl: u32,
+    pub holder: String,
+}
+
+#[test]
+fn param_attr_is_put_on_the_parameter() {
+    let lease = Lease::new(30, "node-a".to_string());
+    assert_eq!((lease.ttl, lease.holder.as_str()), (30, "node-a"));
+}
+
+#[derive(ImplNew)]
+struct Workspace {
+    #[new(as_ref = "std::path::Path")]
+    pub root: std::path::PathBuf,
+    #[new(as_ref = "str")]
+    pub name: String,
+}
+
+#[test]
+fn as_ref_fields_take_anything_referencing_the_target() {
+    let workspace = Workspace::new("/srv/app", "app");
+    assert_eq!(workspace.root, std::path::Path::new("/srv/app"));
+    assert_eq!(workspace.name, "app");
+
+    let workspace = Workspace::new(std::path::PathBuf::from("/tmp"), String::from("scratch"));
+    assert_eq!(workspace.root.to_str(), Some("/tmp"));
+    assert_eq!(workspace.name, "scratch");
+}
@@ -0,0 +1,69 @@
+use impl_new_derive::ImplNew;
+
+#[derive(Debug, ImplNew)]
+#[new(try_new, error = "String")]
+struct Username {
+    #[new(regex = "^[a-z]+$")]
+    pub name: String,
+    pub id: u32,
+}
+
+#[test]
+fn try_new_rejects_parameters_not_matching_the_pattern() {
+    let user = Username::try_new("ada".to_string(), 1).unwrap();
+    assert_eq!((user.name.as_str(), user.id), ("ada", 1));
+    assert_eq!(
+        Username::try_new("Ada".to_string(), 2).unwrap_err(),
+        "Username: `name` does not match `^[a-z]+$`"
+    );
+}
+
+#[derive(Debug, ImplNew)]
+struct Slug {
+    #[new(regex = "^[a-z]+$")]
+    pub value: &'static str,
+}
+
+#[test]
+fn new_accepts_matching_parameters() {
+    assert_eq!(Slug::new("intro").value, "intro");
+}
+
+#[test]
+#[should_panic(expected = "Slug: `value` does not match `^[a-z]+$`")]
+fn new_panics_on_parameters_not_matching_the_pattern() {
+    let _ = Slug::new("Intro!");
+}
+
+#[derive(Debug, ImplNew)]
+#[new(from_map, with_overrides)]
+struct Tag {
+    #[new(regex = "^[a-z][a-z0-9-]*$")]
+    pub label: String,
+    #[new(default(0))]
+    uses: u32,
+}
+
+#[test]
+fn from_map_returns_a_mismatch_as_an_error() {
+    let mut map = std::collections::HashMap::new();
+    map.insert("label".to_string(), "rust-2024".to_string());
+    assert_eq!(Tag::from_map(&map).unwrap().label, "rust-2024");
+
+    map.insert("label".to_string(), "2024-rust".to_string());
+    let err = Tag::from_map(&map).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Tag: `label` does not match `^[a-z][a-z0-9-]*$`"
+    );
+}
+
+#[test]
+#[should_panic(expected = "Tag: `label` does not match `^[a-z][a-z0-9-]*$`")]
+fn with_overrides_checks_the_pattern() {
+    let mut base = Tag::new("docs".to_string());
+    base.uses = 2;
+    let tag = Tag::with(base, "guides".to_string());
+    assert_eq!((tag.label.as_str(), tag.uses), ("guides", 2));
+    let _ = Tag::with(tag, "Guides".to_string());
+}
@@ -0,0 +1,54 @@
+use impl_new_derive::ImplNew;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::sync::Mutex;
+
+static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct Capture;
+
+impl Log for Capture {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Trace
+    }
+
+    fn log(&self, record: &Record) {
+        MESSAGES.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+#[derive(ImplNew)]
+#[new(trace)]
+struct Traced {
+    pub name: String,
+    #[new(group = "pos")]
+    pub x: i32,
+    #[new(group = "pos")]
+    pub y: i32,
+    cache: Vec<u8>,
+}
+
+#[derive(ImplNew)]
+#[new(trace)]
+struct TracedEmpty {}
+
+#[test]
+fn trace_logs_type_name_and_arguments() {
+    log::set_logger(&Capture).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let traced = Traced::new("probe".to_string(), (1, 2));
+    assert_eq!((traced.name.as_str(), traced.x, traced.y), ("probe", 1, 2));
+    assert!(traced.cache.is_empty());
+    let TracedEmpty {} = TracedEmpty::new();
+
+    let messages = MESSAGES.lock().unwrap();
+    assert_eq!(
+        messages.as_slice(),
+        [
+            r#"Traced::new(name: "probe", x: 1, y: 2)"#,
+            "TracedEmpty::new()"
+        ]
+    );
+}
@@ -0,0 +1,103 @@
+use impl_new_derive::ImplNew;
+
+#[derive(Debug, PartialEq, ImplNew)]
+#[new(typed_builder)]
+pub struct Server {
+    pub host: String,
+    pub port: u16,
+    #[new(default(30))]
+    timeout: u64,
+}
+
+#[test]
+fn build_requires_every_parameter_in_any_order() {
+    let server = Server::builder()
+        .port(8080)
+        .host("localhost".to_string())
+        .build();
+    assert_eq!(server, Server::new("localhost".to_string(), 8080));
+    assert_eq!(server.timeout, 30);
+}
+
+#[derive(ImplNew)]
+#[new(typed_builder, try_new)]
+struct Slot<'a, T: Clone, const N: usize> {
+    pub name: &'a str,
+    pub items: [T; N],
+    #[new(nonzero)]
+    pub weight: std::num::NonZeroU8,
+    #[new(group = "pos")]
+    pub x: i32,
+    #[new(group = "pos")]
+    pub y: i32,
+}
+
+#[test]
+fn builder_keeps_generics_conversions_and_the_return_type() {
+    let slot = Slot::builder()
+        .y(2)
+        .weight(3)
+        .items(['a'; 2])
+        .x(1)
+        .name("slot")
+        .build()
+        .unwrap();
+    assert_eq!(
+        (slot.name, slot.items, slot.weight.get()),
+        ("slot", ['a'; 2], 3)
+    );
+    assert_eq!((slot.x, slot.y), (1, 2));
+
+    assert!(Slot::builder()
+        .name("zero")
+        .items([0u8; 1])
+        .weight(0)
+        .x(0)
+        .y(0)
+        .build()
+        .is_err());
+}
+
+#[derive(Debug, ImplNew)]
+#[new(typed_builder, prefix = "Internal")]
+struct Packet {
+    pub id: u16,
+}
+
+/// Would collide with the generated builder without the prefix.
+struct PacketBuilder;
+
+#[test]
+fn prefix_renames_the_generated_builder() {
+    let _ = PacketBuilder;
+    let builder: InternalPacketBuilder<()> = Packet::builder();
+    assert_eq!(builder.id(7).build().id, 7);
+}
+
+#[derive(ImplNew)]
+#[new(typed_builder, expose_builder_fn = "configure")]
+struct Upload {
+    pub path: &'static str,
+    pub chunk_size: usize,
+}
+
+#[test]
+fn expose_builder_fn_names_the_entry_point() {
+    let builder: UploadBuilder<(), ()> = Upload::configure();
+    let upload = builder.chunk_size(4096).path("a.bin").build();
+    assert_eq!((upload.path, upload.chunk_size), ("a.bin", 4096));
+}
+
+#[derive(ImplNew)]
+#[new(typed_builder)]
+struct Chain {
+    pub id: u32,
+    pub next: Option<Box<Self>>,
+}
+
+#[test]
+fn self_in_field_types_works_in_the_builder() {
+    let tail = Chain::builder().id(2).next(None).build();
+    let head = Chain::builder().next(Some(Box::new(tail))).id(1).build();
+    assert_eq!(head.next.map(|next| next.id), Some(2));
+}
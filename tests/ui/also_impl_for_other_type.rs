@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(also_impl_for = "Bytes = Vec<u8>")]
+struct Buffer<T> {
+    pub items: Vec<T>,
+}
+
+fn main() {}
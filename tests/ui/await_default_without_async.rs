@@ -0,0 +1,14 @@
+use impl_new_derive::ImplNew;
+
+async fn fetch_limit() -> u32 {
+    128
+}
+
+#[derive(ImplNew)]
+struct Connection {
+    pub url: &'static str,
+    #[new(default(fetch_limit().await))]
+    limit: u32,
+}
+
+fn main() {}
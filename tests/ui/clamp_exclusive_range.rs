@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Percent {
+    #[new(clamp = "0..100")]
+    pub value: u8,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(const_default)]
+struct Limits {
+    #[new(default(16))]
+    pub max_connections: u32,
+    profile: String,
+}
+
+#[derive(ImplNew)]
+#[new(const_default)]
+struct Named {
+    pub name: &'static str,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(const)]
+struct Journal {
+    pub name: &'static str,
+    entries: Vec<String>,
+}
+
+fn main() {}
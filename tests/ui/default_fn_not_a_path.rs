@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Crawler {
+    #[new(default_fn = "Vec::new()")]
+    queue: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Config {
+    pub name: String,
+    #[default(1 +)]
+    workers: u32,
+}
+
+#[derive(ImplNew)]
+struct Limits {
+    pub name: String,
+    #[default = 5]
+    max: u32,
+}
+
+fn main() {}
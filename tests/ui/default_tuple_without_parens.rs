@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Labeled {
+    pub id: u8,
+    #[new(default(1, "x".to_string()))]
+    pair: (i32, String),
+}
+
+fn main() {}
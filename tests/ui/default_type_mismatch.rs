@@ -0,0 +1,14 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Config {
+    pub name: String,
+    #[new(default("thirty"))]
+    timeout: u64,
+    #[new(default)]
+    pub retries: NoDefault,
+}
+
+struct NoDefault;
+
+fn main() {}
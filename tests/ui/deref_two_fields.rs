@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(deref)]
+struct Pair {
+    pub left: u8,
+    pub right: u8,
+}
+
+fn main() {}
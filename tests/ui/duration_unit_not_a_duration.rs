@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Timeout {
+    #[new(from_millis)]
+    pub after: u64,
+}
+
+fn main() {}
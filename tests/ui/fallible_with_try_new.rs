@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(fallible, try_new)]
+struct Account {
+    pub owner: String,
+}
+
+fn main() {}
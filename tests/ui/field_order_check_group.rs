@@ -0,0 +1,13 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(field_order_check)]
+struct Ledger {
+    #[new(group = "amount")]
+    pub units: u64,
+    pub account: String,
+    #[new(group = "amount")]
+    pub cents: u8,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(from)]
+struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+fn main() {}
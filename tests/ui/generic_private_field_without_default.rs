@@ -0,0 +1,13 @@
+use impl_new_derive::ImplNew;
+
+struct NoDefault;
+
+#[derive(ImplNew)]
+struct Accumulator<T> {
+    pub label: &'static str,
+    total: T,
+}
+
+fn main() {
+    let _ = Accumulator::<NoDefault>::new("sum");
+}
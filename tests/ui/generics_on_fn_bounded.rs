@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(generics_on_fn)]
+struct Cached<K, V: Default> {
+    pub key: K,
+    value: V,
+}
+
+fn main() {}
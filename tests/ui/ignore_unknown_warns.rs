@@ -0,0 +1,16 @@
+#![deny(deprecated)]
+
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(skip_serializing_defaults, ignore_unknown)]
+struct Settings {
+    pub name: &'static str,
+    #[new(future_key = "value", default(3))]
+    retries: u8,
+}
+
+fn main() {
+    let settings = Settings::new("app");
+    let _ = (settings.name, settings.retries);
+}
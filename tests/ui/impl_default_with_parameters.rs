@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(impl_default)]
+struct Point {
+    pub x: i32,
+    label: String,
+}
+
+fn main() {}
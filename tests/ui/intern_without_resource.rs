@@ -0,0 +1,11 @@
+use impl_new_derive::ImplNew;
+
+struct Symbol(usize);
+
+#[derive(ImplNew)]
+struct Binding {
+    #[new(intern = "interner")]
+    pub name: Symbol,
+}
+
+fn main() {}
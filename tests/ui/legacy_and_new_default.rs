@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Retry {
+    #[default(3)]
+    #[new(default(5))]
+    attempts: u8,
+}
+
+fn main() {}
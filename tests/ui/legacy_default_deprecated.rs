@@ -0,0 +1,15 @@
+#![deny(deprecated)]
+
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Legacy {
+    pub name: &'static str,
+    #[default(42)]
+    answer: u32,
+}
+
+fn main() {
+    let legacy = Legacy::new("old");
+    let _ = (legacy.name, legacy.answer);
+}
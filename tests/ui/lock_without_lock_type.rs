@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Counter {
+    #[new(lock)]
+    pub count: std::cell::RefCell<u32>,
+}
+
+fn main() {}
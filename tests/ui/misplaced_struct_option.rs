@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Config {
+    #[new(try_new)]
+    pub name: String,
+}
+
+fn main() {}
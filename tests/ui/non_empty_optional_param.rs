@@ -0,0 +1,11 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(optional_params)]
+struct Channel {
+    pub id: u32,
+    #[new(non_empty, default(vec!["general".to_string()]))]
+    pub topics: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(params(host, prot))]
+struct Endpoint {
+    host: String,
+    port: u16,
+}
+
+fn main() {}
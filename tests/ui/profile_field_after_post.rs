@@ -0,0 +1,11 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(post = "value.total = value.count * 2")]
+#[new(profile(name = "busy", defaults(count = 8, total = 100)))]
+struct Stats {
+    pub count: u32,
+    total: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(profile(name = "small", defaults(cap = 16)))]
+struct Buffer {
+    pub cap: usize,
+    pub label: &'static str,
+}
+
+fn main() {}
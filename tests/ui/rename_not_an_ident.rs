@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Token {
+    #[new(rename = "two words")]
+    pub kind: u8,
+}
+
+fn main() {}
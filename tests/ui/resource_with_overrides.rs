@@ -0,0 +1,21 @@
+use impl_new_derive::ImplNew;
+
+struct Symbol(usize);
+
+struct Interner(Vec<String>);
+
+impl Interner {
+    fn intern(&mut self, name: &str) -> Symbol {
+        self.0.push(name.to_string());
+        Symbol(self.0.len() - 1)
+    }
+}
+
+#[derive(ImplNew)]
+#[new(resource(interner: &mut Interner), with_overrides)]
+struct Binding {
+    #[new(intern = "interner")]
+    pub name: Symbol,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Service<T> {
+    #[new(skip_default_bound)]
+    pub config: T,
+}
+
+fn main() {}
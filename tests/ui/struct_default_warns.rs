@@ -0,0 +1,26 @@
+#![deny(deprecated)]
+
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(default, ignore_unknown)]
+struct Settings {
+    #[default(4)]
+    pub workers: usize,
+    #[new(skip_serializing)]
+    pub name: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            workers: 1,
+            name: String::new(),
+        }
+    }
+}
+
+fn main() {
+    let settings = Settings::new();
+    let _ = (settings.workers, settings.name);
+}
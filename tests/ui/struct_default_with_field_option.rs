@@ -0,0 +1,12 @@
+use impl_new_derive::ImplNew;
+
+#[derive(Default, ImplNew)]
+#[new(default)]
+struct Settings {
+    #[new(default(4))]
+    pub workers: usize,
+    #[new(non_empty)]
+    pub name: String,
+}
+
+fn main() {}
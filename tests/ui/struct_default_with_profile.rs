@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(Default, ImplNew)]
+#[new(private)]
+#[new(profile(name = "quiet", defaults(verbose = false)), default)]
+struct Settings {
+    pub verbose: bool,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(Default, ImplNew)]
+#[new(default, try_new)]
+struct Settings {
+    pub verbose: bool,
+}
+
+fn main() {}
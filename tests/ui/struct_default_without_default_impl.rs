@@ -0,0 +1,11 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(default)]
+struct Settings {
+    pub verbose: bool,
+}
+
+fn main() {
+    let _ = Settings::new();
+}
@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Job {
+    pub name: String,
+    #[new(timed)]
+    took_ms: u64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Config {
+    pub name: String,
+    #[new(default("8".parse::<u32>()?))]
+    workers: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Worker {
+    pub job: u32,
+    #[new(type_name)]
+    kind: u64,
+}
+
+fn main() {}
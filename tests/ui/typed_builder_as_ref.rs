@@ -0,0 +1,11 @@
+use impl_new_derive::ImplNew;
+use std::path::PathBuf;
+
+#[derive(ImplNew)]
+#[new(typed_builder)]
+struct Mount {
+    #[new(as_ref = "std::path::Path")]
+    pub root: PathBuf,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(typed_builder)]
+struct Server {
+    pub host: String,
+    pub port: u16,
+}
+
+fn main() {
+    let _ = Server::builder().host("localhost".to_string()).build();
+}
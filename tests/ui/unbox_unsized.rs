@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Frame {
+    #[new(unbox)]
+    pub label: str,
+}
+
+fn main() {}
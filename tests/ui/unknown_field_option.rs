@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Config {
+    #[new(defualt(8080))]
+    pub port: u16,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(privat)]
+struct Config {
+    pub name: String,
+}
+
+fn main() {}
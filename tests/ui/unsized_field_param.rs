@@ -0,0 +1,19 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+struct Name {
+    pub id: u32,
+    pub value: str,
+}
+
+#[derive(ImplNew)]
+struct Bytes {
+    pub data: [u8],
+}
+
+#[derive(ImplNew)]
+struct Handler {
+    pub callback: dyn Fn(),
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use impl_new_derive::ImplNew;
+
+fn check(_: &Window) -> Result<(), String> {
+    Ok(())
+}
+
+#[derive(ImplNew)]
+#[new(validate_fn = "check")]
+struct Window {
+    pub min: u32,
+}
+
+fn main() {}
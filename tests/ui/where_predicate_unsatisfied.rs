@@ -0,0 +1,12 @@
+use impl_new_derive::ImplNew;
+use std::rc::Rc;
+
+#[derive(ImplNew)]
+#[new(where = "T: Send")]
+struct Job<T> {
+    pub payload: T,
+}
+
+fn main() {
+    let _ = Job::new(Rc::new(1));
+}
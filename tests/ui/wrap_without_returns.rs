@@ -0,0 +1,9 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(wrap = "std::rc::Rc::new")]
+struct Node {
+    pub id: u32,
+}
+
+fn main() {}
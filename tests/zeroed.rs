@@ -0,0 +1,18 @@
+use impl_new_derive::ImplNew;
+
+#[derive(ImplNew)]
+#[new(zeroed)]
+struct Vertex {
+    pub id: u32,
+    weights: [u32; 300],
+    #[new(default(1))]
+    scale: u32,
+}
+
+#[test]
+fn zeroed_initializes_fields_without_default() {
+    let vertex = Vertex::new(9);
+    assert_eq!(vertex.id, 9);
+    assert!(vertex.weights.iter().all(|&w| w == 0));
+    assert_eq!(vertex.scale, 1);
+}